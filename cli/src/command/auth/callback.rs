@@ -0,0 +1,56 @@
+use std::future::Future;
+
+use anyhow::{bail, Result};
+use clap::Args;
+use slot::server::CallbackAddr;
+
+/// Options of the local server receiving the result of the browser flows.
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Callback options")]
+pub struct CallbackArgs {
+    #[arg(long, default_value_t = 0)]
+    #[arg(value_name = "PORT")]
+    #[arg(help = "The port of the local callback server. A random port is used by default.")]
+    callback_port: u16,
+
+    #[arg(long, default_value = "localhost")]
+    #[arg(value_name = "HOST")]
+    #[arg(help = "The host the local callback server binds to, e.g. 0.0.0.0 inside a container.")]
+    callback_host: String,
+}
+
+impl CallbackArgs {
+    pub fn addr(&self) -> CallbackAddr {
+        CallbackAddr {
+            host: self.callback_host.clone(),
+            port: self.callback_port,
+        }
+    }
+
+    /// Explains how to forward the callback port, when it is fixed to run in a container or on
+    /// a remote machine.
+    pub fn print_forwarding_hint(&self) {
+        let port = self.callback_port;
+        if port == 0 {
+            return;
+        }
+
+        println!(
+            "Waiting for the browser on port {port}. If slot runs in a container or on a remote \
+             machine, forward the port to the machine running the browser, e.g. with \
+             `docker run -p {port}:{port} ...` (and `--callback-host 0.0.0.0`) or \
+             `ssh -L {port}:localhost:{port} ...`.\n"
+        );
+    }
+}
+
+/// Runs `flow`, which waits for the browser, until it completes or the user presses Ctrl-C.
+pub async fn until_cancelled<T, E>(flow: impl Future<Output = Result<T, E>>) -> Result<T>
+where
+    anyhow::Error: From<E>,
+{
+    tokio::select! {
+        result = flow => Ok(result?),
+        _ = tokio::signal::ctrl_c() => bail!("Cancelled while waiting for the browser."),
+    }
+}
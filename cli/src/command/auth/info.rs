@@ -1,20 +1,31 @@
 use anyhow::Result;
 use clap::Args;
 use colored::*;
+use serde::Serialize;
 use slot::graphql::auth::{me::*, Me};
 use slot::graphql::GraphQLQuery;
 use slot::{api::Client, credential::Credentials};
 
+use crate::output::{self, Render};
+
 #[derive(Debug, Args)]
 pub struct InfoArgs;
 
-fn format_usd(credits: i64) -> String {
-    // format two digits currency
-    let amount = credits as f64 / 100f64;
-    // format two digits e.g. $1.02
+fn format_usd(amount: f64) -> String {
+    // format two digits currency e.g. $1.02
     format!("${:.2}", amount)
 }
 
+/// Converts an amount of cents to USD.
+fn cents_to_usd(cents: i64) -> f64 {
+    cents as f64 / 100f64
+}
+
+/// Converts a team amount, in millionths of a cent, to USD rounded to the cent.
+fn team_amount_to_usd(amount: i64) -> f64 {
+    cents_to_usd((amount as f64 / 1e6) as i64)
+}
+
 impl InfoArgs {
     // TODO: find the account info from `credentials.json` first before making a request
     pub async fn run(&self) -> Result<()> {
@@ -23,78 +34,138 @@ impl InfoArgs {
 
         let request_body = Me::build_query(Variables {});
         let res: ResponseData = client.query(&request_body).await?;
-        let info = res.me.clone().unwrap();
-        println!("Username: {}", info.username);
-        println!(
-            "Balance: {}",
-            // round usd to 2 digits
-            format_usd(info.credits_plain)
-        );
+        let info = res.me.unwrap();
+
+        let mut teams = Vec::new();
+        for edge in info.teams.edges.unwrap() {
+            let team = edge.unwrap().node.unwrap();
+            if team.deleted {
+                continue;
+            }
+
+            // Determine total balance based on incubator stage, in cents
+            let total_balance = match &team.incubator_stage {
+                Some(TeamIncubatorStage::senpai) => 500000, // $5k in cents
+                Some(TeamIncubatorStage::sensei) => 2500000, // $25k in cents
+                _ => 0,
+            };
+            let total_spent = (team.total_debits as f64 / 1e6) as i64;
+
+            let deployments = team
+                .deployments
+                .edges
+                .unwrap()
+                .into_iter()
+                .flatten()
+                .filter_map(|edge| edge.node)
+                .filter(|deployment| !matches!(deployment.status, DeploymentStatus::deleted))
+                .map(|deployment| TeamDeployment {
+                    project: deployment.project,
+                    service: deployment.service_id,
+                    deprecated: deployment.deprecated.unwrap_or(false),
+                })
+                .collect();
+
+            let members = team
+                .membership
+                .edges
+                .unwrap()
+                .into_iter()
+                .map(|edge| edge.unwrap().node.unwrap())
+                .map(|member| TeamMember {
+                    username: member.account.username,
+                    role: member.role,
+                })
+                .collect();
+
+            teams.push(TeamInfo {
+                name: team.name,
+                balance: team_amount_to_usd(team.credits),
+                incubator_stage: team.incubator_stage,
+                total_balance: cents_to_usd(total_balance),
+                total_spent: cents_to_usd(total_spent),
+                remaining_incubator_credits: cents_to_usd(total_balance - total_spent),
+                deployments,
+                members,
+            });
+        }
+
+        output::print(&AccountSummary {
+            username: info.username,
+            balance: cents_to_usd(info.credits_plain),
+            teams,
+        })
+    }
+}
+
+/// The account of the user. The amounts are in USD.
+#[derive(Debug, Serialize)]
+struct AccountSummary {
+    username: String,
+    balance: f64,
+    teams: Vec<TeamInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct TeamInfo {
+    name: String,
+    balance: f64,
+    incubator_stage: Option<TeamIncubatorStage>,
+    total_balance: f64,
+    total_spent: f64,
+    remaining_incubator_credits: f64,
+    deployments: Vec<TeamDeployment>,
+    members: Vec<TeamMember>,
+}
+
+#[derive(Debug, Serialize)]
+struct TeamDeployment {
+    project: String,
+    service: String,
+    deprecated: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct TeamMember {
+    username: String,
+    role: AccountTeamRole,
+}
+
+impl Render for AccountSummary {
+    fn print_table(&self) {
+        println!("Username: {}", self.username);
+        println!("Balance: {}", format_usd(self.balance));
 
         println!();
         println!("Teams:");
-        let teams = res.me.unwrap().teams.edges.unwrap();
 
-        if teams.is_empty() {
+        if self.teams.is_empty() {
             println!("  No teams yet");
         }
 
-        for edge in teams {
-            let team = edge.unwrap().node.unwrap();
-            if team.deleted {
-                continue;
-            }
+        for team in &self.teams {
             println!();
             println!("  Name: {}", team.name);
-            println!(
-                "  Balance: {}",
-                // round usd to 2 digits
-                format_usd((team.credits as f64 / 1e6) as i64)
-            );
+            println!("  Balance: {}", format_usd(team.balance));
 
-            let total_balance = if let Some(incubator_stage) = &team.incubator_stage {
+            if let Some(incubator_stage) = &team.incubator_stage {
                 println!("  Incubator Stage: {:?}", incubator_stage);
+            }
 
-                // Determine total balance based on incubator stage
-                // Match against the Debug representation of the enum
-                match format!("{:?}", incubator_stage).as_str() {
-                    "senpai" => 500000,  // $5k in cents
-                    "sensei" => 2500000, // $25k in cents
-                    _ => 0,
-                }
-            } else {
-                0
-            };
-
-            println!("  Total Balance: {}", format_usd(total_balance));
-
-            println!(
-                "  Total Spent: {}",
-                format_usd((team.total_debits as f64 / 1e6) as i64)
-            );
-
-            // Calculate remaining balance
-            let remaining_balance = total_balance - ((team.total_debits as f64 / 1e6) as i64);
+            println!("  Total Balance: {}", format_usd(team.total_balance));
+            println!("  Total Spent: {}", format_usd(team.total_spent));
             println!(
                 "  Remaining Incubator Credits: {}",
-                format_usd(remaining_balance)
+                format_usd(team.remaining_incubator_credits)
             );
 
             println!("  Deployments:");
-            let deployments = team.deployments.edges.unwrap();
-            let active_deployments: Vec<_> = deployments
-                .iter()
-                .filter_map(|edge| edge.as_ref())
-                .filter_map(|edge| edge.node.as_ref())
-                .filter(|deployment| format!("{:?}", deployment.status) != "deleted")
-                .collect();
-
-            if active_deployments.is_empty() {
+            if team.deployments.is_empty() {
                 println!("    No deployments yet");
             }
 
-            for deployment in active_deployments {
-                let deprecated_indicator = if deployment.deprecated.unwrap_or(false) {
+            for deployment in &team.deployments {
+                let deprecated_indicator = if deployment.deprecated {
                     format!(" {}", "(deprecated!)".bold())
                 } else {
                     String::new()
@@ -102,21 +173,14 @@ impl InfoArgs {
 
                 println!(
                     "    Deployment: {}/{}{}",
-                    deployment.project, deployment.service_id, deprecated_indicator
+                    deployment.project, deployment.service, deprecated_indicator
                 );
             }
 
             println!("  Members:");
-            let members = team.membership.edges.unwrap();
-            for edge in members {
-                let member = edge.unwrap().node.unwrap();
-                println!(
-                    "    Member: {} ({:?})",
-                    member.account.username, member.role
-                );
+            for member in &team.members {
+                println!("    Member: {} ({:?})", member.username, member.role);
             }
         }
-
-        Ok(())
     }
 }
@@ -8,6 +8,7 @@ use axum::{
     Router,
 };
 use clap::Args;
+use colored::*;
 use graphql_client::GraphQLQuery;
 use hyper::StatusCode;
 use log::error;
@@ -16,36 +17,73 @@ use slot::{
     account::AccountInfo,
     api::Client,
     browser,
-    credential::Credentials,
+    credential::{AccessToken, Credentials},
     graphql::auth::{
         me::{ResponseData, Variables},
         Me,
     },
-    server::LocalServer,
+    server::{self, CallbackAddr, LocalServer},
     vars,
 };
 use tokio::sync::mpsc::Sender;
+use url::Url;
+
+use super::callback::{self, CallbackArgs};
 
 #[derive(Debug, Args)]
-pub struct LoginArgs;
+pub struct LoginArgs {
+    #[arg(long, visible_alias = "no-browser")]
+    #[arg(help = "Approve the login from another device, e.g. when running over SSH.")]
+    device: bool,
+
+    #[command(flatten)]
+    callback: CallbackArgs,
+}
 
 impl LoginArgs {
     pub async fn run(&self) -> Result<()> {
-        let server = Self::callback_server().expect("Failed to create a server");
-        let port = server.local_addr()?.port();
-        let callback_uri = format!("http://localhost:{port}/callback");
+        if self.device {
+            return Self::device_login().await;
+        }
+
+        let server = Self::callback_server(&self.callback.addr())?;
+        let callback_uri = server.callback_uri("/callback")?;
 
         let url = vars::get_cartridge_keychain_url();
+        let mut url = Url::parse(&format!("{url}/slot"))?;
+        url.query_pairs_mut()
+            .append_pair("callback_uri", callback_uri.as_str());
 
-        let url = format!("{url}/slot?callback_uri={callback_uri}");
+        browser::open(url.as_str())?;
+        self.callback.print_forwarding_hint();
+        callback::until_cancelled(server.start()).await?;
 
-        browser::open(&url)?;
-        server.start().await?;
+        Ok(())
+    }
+
+    async fn device_login() -> Result<()> {
+        let mut api = Client::new();
+        let authorization = api.device_authorization().await?;
 
+        println!(
+            "To login, open this URL on any device:\n\n    {}\n",
+            authorization.verification_uri
+        );
+        println!("And enter the code: {}\n", authorization.user_code.bold());
+
+        if let Some(uri) = &authorization.verification_uri_complete {
+            println!("Or open this URL, with the code already filled in:\n\n    {uri}\n");
+        }
+
+        println!("Waiting for the login to be approved...");
+        let token = api.poll_device_token(&authorization).await?;
+        store_credentials(&mut api, token).await?;
+
+        println!("You are now logged in!\n");
         Ok(())
     }
 
-    fn callback_server() -> Result<LocalServer> {
+    fn callback_server(addr: &CallbackAddr) -> Result<LocalServer> {
         let (tx, rx) = tokio::sync::mpsc::channel::<()>(1);
         let shared_state = Arc::new(AppState::new(tx));
 
@@ -53,7 +91,9 @@ impl LoginArgs {
             .route("/callback", get(handler))
             .with_state(shared_state);
 
-        Ok(LocalServer::new(router)?.with_shutdown_signal(rx))
+        Ok(LocalServer::bind(router, addr)?
+            .with_shutdown_signal(rx)
+            .with_deadline(server::DEFAULT_DEADLINE))
     }
 }
 
@@ -108,17 +148,9 @@ async fn handler(
             let mut api = Client::new();
 
             let token = api.oauth2(&code).await?;
-            api.set_token(token.clone());
-
-            // fetch the account information
-            let request_body = Me::build_query(Variables {});
-            let data: ResponseData = api.query(&request_body).await?;
-
-            let account = data.me.expect("missing payload");
-            let account = AccountInfo::from(account);
 
             // 3. Store the access token locally
-            Credentials::new(account, token).store()?;
+            store_credentials(&mut api, token).await?;
 
             println!("You are now logged in!\n");
 
@@ -137,3 +169,19 @@ async fn handler(
         }
     }
 }
+
+/// Fetches the account information of the user authenticated by `token`, and stores the
+/// credentials locally.
+async fn store_credentials(api: &mut Client, token: AccessToken) -> Result<(), slot::Error> {
+    api.set_token(token.clone());
+
+    // fetch the account information
+    let request_body = Me::build_query(Variables {});
+    let data: ResponseData = api.query(&request_body).await?;
+
+    let account = data.me.expect("missing payload");
+    let account = AccountInfo::from(account);
+
+    Credentials::new(account, token).store()?;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use clap::Args;
+use slot::api::Client;
+use slot::credential::Credentials;
+use slot::profile;
+use slot::session;
+
+#[derive(Debug, Args)]
+pub struct LogoutArgs {
+    #[arg(long)]
+    #[arg(help = "Logout of all the profiles instead of only the active one.")]
+    all_profiles: bool,
+
+    #[arg(long)]
+    #[arg(help = "Also revoke the stored sessions server-side, before removing them.")]
+    revoke_sessions: bool,
+}
+
+impl LogoutArgs {
+    pub async fn run(&self) -> Result<()> {
+        let profiles = if self.all_profiles {
+            profile::list()?
+        } else {
+            vec![profile::active()?]
+        };
+
+        for name in profiles {
+            if self.revoke_sessions {
+                revoke_sessions(&name).await?;
+            }
+
+            if profile::logout(&name)? {
+                println!("Logged out of profile '{name}'.");
+            } else {
+                println!("Profile '{name}' is not logged in.");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Revokes the sessions of the profile. The local files are kept if this fails so that it can be
+/// retried.
+async fn revoke_sessions(name: &str) -> Result<()> {
+    let Ok(credentials) = Credentials::load_profile(name) else {
+        return Ok(());
+    };
+
+    let sessions = session::list_profile(name)?;
+    if sessions.is_empty() {
+        return Ok(());
+    }
+
+    let client = Client::new_with_token(credentials.access_token);
+    session::revoke(&client, &sessions).await?;
+    println!("Revoked {} session(s) of profile '{name}'.", sessions.len());
+
+    Ok(())
+}
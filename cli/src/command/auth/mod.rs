@@ -1,13 +1,19 @@
-use self::{email::EmailArgs, info::InfoArgs, login::LoginArgs, token::TokenArgs};
+use self::{
+    email::EmailArgs, info::InfoArgs, login::LoginArgs, logout::LogoutArgs, token::TokenArgs,
+};
 use crate::command::auth::fund::FundArgs;
+use crate::command::auth::profiles::ProfilesCmd;
 use crate::command::auth::transfer::TransferArgs;
 use anyhow::Result;
 use clap::Subcommand;
 
+pub(crate) mod callback;
 mod email;
 mod fund;
 mod info;
 mod login;
+mod logout;
+mod profiles;
 mod session;
 mod token;
 mod transfer;
@@ -17,6 +23,11 @@ pub enum Auth {
     #[command(about = "Login to your Cartridge account.")]
     Login(LoginArgs),
 
+    #[command(
+        about = "Logout of your Cartridge account, removing the stored credentials and sessions."
+    )]
+    Logout(LogoutArgs),
+
     #[command(about = "Display info about the authenticated user.")]
     Info(InfoArgs),
 
@@ -32,6 +43,10 @@ pub enum Auth {
     #[command(about = "Display the current auth token.")]
     Token(TokenArgs),
 
+    #[command(subcommand)]
+    #[command(about = "Manage the credentials profiles.")]
+    Profiles(ProfilesCmd),
+
     // Mostly for testing purposes, will eventually turn it into a library call from `sozo`.
     #[command(hide = true)]
     CreateSession(session::CreateSession),
@@ -41,12 +56,18 @@ impl Auth {
     pub async fn run(&self) -> Result<()> {
         match &self {
             Auth::Login(args) => args.run().await,
+            Auth::Logout(args) => args.run().await,
             Auth::Info(args) => args.run().await,
             Auth::CreateSession(args) => args.run().await,
             Auth::SetEmail(args) => args.run().await,
             Auth::Fund(args) => args.run().await,
             Auth::Transfer(args) => args.run().await,
             Auth::Token(args) => args.run().await,
+            Auth::Profiles(cmd) => cmd.run().await,
         }
     }
+
+    pub fn renders_output(&self) -> bool {
+        matches!(self, Auth::Info(_))
+    }
 }
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use colored::*;
+use slot::credential::Credentials;
+use slot::profile;
+
+#[derive(Subcommand, Debug)]
+pub enum ProfilesCmd {
+    #[command(about = "List the profiles with stored credentials.", aliases = ["ls"])]
+    List,
+
+    #[command(about = "Set the profile used by default.")]
+    Use(ProfileArgs),
+
+    #[command(about = "Remove a profile, including its credentials and sessions.")]
+    Remove(ProfileArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ProfileArgs {
+    #[arg(help = "The name of the profile.")]
+    name: String,
+}
+
+impl ProfilesCmd {
+    pub async fn run(&self) -> Result<()> {
+        match self {
+            Self::List => list(),
+            Self::Use(args) => use_profile(&args.name),
+            Self::Remove(args) => remove(&args.name),
+        }
+    }
+}
+
+fn list() -> Result<()> {
+    let active = profile::active()?;
+    let profiles = profile::list()?;
+
+    if profiles.is_empty() {
+        println!("No profiles yet, login with `slot auth login` to create one.");
+        return Ok(());
+    }
+
+    for name in profiles {
+        let username = Credentials::load_profile(&name)
+            .map(|c| c.account.username)
+            .unwrap_or_else(|_| "<malformed credentials>".to_string());
+
+        if name == active {
+            println!("* {} ({})", name.green().bold(), username);
+        } else {
+            println!("  {} ({})", name, username);
+        }
+    }
+
+    Ok(())
+}
+
+fn use_profile(name: &str) -> Result<()> {
+    profile::set_current(name)?;
+
+    if Credentials::load_profile(name).is_ok() {
+        println!("Now using profile '{name}'.");
+    } else {
+        println!("Now using profile '{name}'. Login with `slot auth login` to authenticate it.");
+    }
+
+    Ok(())
+}
+
+fn remove(name: &str) -> Result<()> {
+    profile::remove(name)?;
+    println!("Profile '{name}' removed.");
+    Ok(())
+}
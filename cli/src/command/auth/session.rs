@@ -1,11 +1,23 @@
+use std::env;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
-use anyhow::{anyhow, ensure, Result};
+use anyhow::{anyhow, ensure, Context, Result};
 use clap::Parser;
-use slot::session::{self, PolicyMethod};
+use dialoguer::Password;
+use slot::preset::load_preset;
+use slot::session::{self, PolicyMethod, SessionOptions, SessionPolicy, TypedDataPolicy};
 use starknet::core::types::Felt;
+use starknet::signers::SigningKey;
 use url::Url;
 
+use super::callback::{self, CallbackArgs};
+use crate::command::paymaster::utils::parse_duration;
+
+/// The environment variable holding the password of the keystore file.
+const KEYSTORE_PASSWORD_ENV_VAR: &str = "SLOT_KEYSTORE_PASSWORD";
+
 #[derive(Debug, Parser)]
 pub struct CreateSession {
     #[arg(long)]
@@ -16,19 +28,102 @@ pub struct CreateSession {
 
     #[arg(help = "The session's policies.")]
     #[arg(value_parser = parse_policy_method)]
-    #[arg(required = true)]
+    #[arg(required_unless_present_any = ["preset", "typed_data"], conflicts_with = "preset")]
     policies: Vec<PolicyMethod>,
+
+    #[arg(long)]
+    #[arg(value_name = "DOMAIN_HASH,TYPE_HASH")]
+    #[arg(value_parser = parse_typed_data_policy)]
+    #[arg(conflicts_with = "preset")]
+    #[arg(help = "Allow the session to sign the typed data messages of a domain and type.")]
+    typed_data: Vec<TypedDataPolicy>,
+
+    #[arg(long)]
+    #[arg(value_name = "NAME")]
+    #[arg(help = "Use the policies of a preset for the network's chain instead.")]
+    preset: Option<String>,
+
+    #[arg(long, requires = "preset")]
+    #[arg(help = "Only request the methods that the preset marks as required.")]
+    required_only: bool,
+
+    #[arg(long, env = "SLOT_SESSION_PRIVATE_KEY", hide_env_values = true)]
+    #[arg(value_name = "PRIVATE_KEY")]
+    #[arg(conflicts_with = "keystore")]
+    #[arg(help = "The private key of the session signer. A random one is generated by default.")]
+    private_key: Option<Felt>,
+
+    #[arg(long)]
+    #[arg(value_name = "PATH")]
+    #[arg(help = "Use the key of a Starknet keystore file as the session signer.")]
+    keystore: Option<PathBuf>,
+
+    #[arg(long)]
+    #[arg(value_name = "DURATION")]
+    #[arg(value_parser = parse_duration)]
+    #[arg(help = "The maximum lifetime of the session, e.g. 1hr or 2days.")]
+    max_lifetime: Option<Duration>,
+
+    #[arg(long, conflicts_with_all = ["callback_port", "callback_host"])]
+    #[arg(help = "Paste the session shown by the keychain instead of receiving it locally.")]
+    paste: bool,
+
+    #[command(flatten)]
+    callback: CallbackArgs,
 }
 
 impl CreateSession {
     pub async fn run(&self) -> Result<()> {
         let url = Url::parse(&self.rpc_url)?;
-        let session = session::create(url, &self.policies).await?;
+        let options = SessionOptions {
+            signer: self.signer()?,
+            max_lifetime: self.max_lifetime,
+            callback: self.callback.addr(),
+            paste: self.paste,
+        };
+
+        self.callback.print_forwarding_hint();
+
+        let session = if let Some(name) = &self.preset {
+            let preset = load_preset(name).await?;
+            let create = session::create_from_preset(url, &preset, self.required_only, &options);
+            callback::until_cancelled(create).await?
+        } else {
+            let calls = self.policies.iter().cloned().map(SessionPolicy::from);
+            let typed_data = self.typed_data.iter().cloned().map(SessionPolicy::from);
+            let policies = calls.chain(typed_data).collect::<Vec<_>>();
+            let create = session::create_with_options(url, &policies, &options);
+            callback::until_cancelled(create).await?
+        };
+
         session::store(session.chain_id, &session)?;
         Ok(())
     }
 }
 
+impl CreateSession {
+    fn signer(&self) -> Result<Option<SigningKey>> {
+        if let Some(private_key) = self.private_key {
+            return Ok(Some(SigningKey::from_secret_scalar(private_key)));
+        }
+
+        if let Some(path) = &self.keystore {
+            let password = match env::var(KEYSTORE_PASSWORD_ENV_VAR) {
+                Ok(password) => password,
+                Err(_) => Password::new()
+                    .with_prompt("Enter the keystore password")
+                    .interact()?,
+            };
+
+            let signer = SigningKey::from_keystore(path, &password)
+                .with_context(|| format!("Failed to read keystore {}", path.display()))?;
+            return Ok(Some(signer));
+        }
+
+        Ok(None)
+    }
+}
+
 fn parse_policy_method(value: &str) -> Result<PolicyMethod> {
     let mut parts = value.split(',');
 
@@ -40,3 +135,19 @@ fn parse_policy_method(value: &str) -> Result<PolicyMethod> {
 
     Ok(PolicyMethod { target, method })
 }
+
+fn parse_typed_data_policy(value: &str) -> Result<TypedDataPolicy> {
+    let mut parts = value.split(',');
+
+    let domain_hash = parts.next().ok_or(anyhow!("missing domain hash"))?;
+    let domain_hash = Felt::from_str(domain_hash)?;
+    let type_hash = parts.next().ok_or(anyhow!("missing type hash"))?;
+    let type_hash = Felt::from_str(type_hash)?;
+
+    ensure!(parts.next().is_none());
+
+    Ok(TypedDataPolicy {
+        domain_hash,
+        type_hash,
+    })
+}
@@ -11,8 +11,9 @@ use slot::credential::Credentials;
 use slot::graphql::deployments::create_deployment::*;
 use slot::graphql::deployments::CreateDeployment;
 use slot::graphql::GraphQLQuery;
+use slot::manifest::Service;
 
-use super::{services::CreateServiceCommands, Tier};
+use super::{services::CreateServiceCommands, validate, Tier};
 
 #[derive(Debug, Args)]
 #[command(next_help_heading = "Create options")]
@@ -53,6 +54,17 @@ pub struct CreateArgs {
 
 impl CreateArgs {
     pub async fn run(&self) -> Result<()> {
+        // Validate the configuration before asking for any confirmation
+        match &self.create_commands {
+            CreateServiceCommands::Katana(config) => {
+                config.validate()?;
+                validate::check_katana(config.config.as_deref(), &config.options())?;
+            }
+            CreateServiceCommands::Torii(config) => {
+                validate::check_config(Service::Torii, Some(config.config.as_path()))?;
+            }
+        }
+
         let tier_pricing = vec![
             (Tier::Basic, "3"),
             (Tier::Pro, "50"),
@@ -106,9 +118,6 @@ impl CreateArgs {
 
         let service = match &self.create_commands {
             CreateServiceCommands::Katana(config) => {
-                // Validate that either config or optimistic is provided
-                config.validate()?;
-
                 // Read the raw config file content if provided
                 let service_config = if let Some(config_path) = &config.config {
                     let content = std::fs::read_to_string(config_path)?;
@@ -1,10 +1,13 @@
 #![allow(clippy::enum_variant_names)]
 
-use crate::command::deployments::print_config_file;
+use crate::command::deployments::{api_name, print_config_file};
+use crate::output::{self, Render};
 
 use super::services::Service;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Args;
+use serde::Serialize;
+use slot::config;
 use slot::graphql::deployments::{describe_deployment::*, DescribeDeployment};
 use slot::graphql::GraphQLQuery;
 use slot::{api::Client, credential::Credentials};
@@ -36,39 +39,71 @@ impl DescribeArgs {
 
         let data: ResponseData = client.query(&request_body).await?;
 
-        if let Some(deployment) = data.deployment {
-            println!("Project: {}", deployment.project);
-            println!("Version: {}", deployment.version);
-
-            if deployment.deprecated.unwrap_or(false) {
-                println!();
-                println!("NOTE:");
-                println!("This deployment is deprecated and immutable.");
-                println!("Please delete it and re-create. Note that this will reset the storage.");
-                println!();
-            }
-
-            println!(
-                "Branch: {}",
-                deployment.branch.unwrap_or_else(|| String::from("Default"))
-            );
-            println!("Tier: {:?}", deployment.tier);
-
-            println!(
-                "Url: {}",
-                super::service_url(&deployment.project, &self.service.to_string())
-            );
-
-            // convert config of type String to &str
-            print_config_file(&deployment.config.config_file);
-
-            if deployment.error.is_some() {
-                println!("\n─────────────── ERROR INFO ───────────────");
-                println!("Error: {}", deployment.error.unwrap());
-                println!("\n─────────────── ERROR INFO ───────────────");
-            }
+        let deployment = data
+            .deployment
+            .with_context(|| format!("Deployment {}/{} not found", self.project, self.service))?;
+
+        let description = DeploymentDescription {
+            url: super::service_url(&deployment.project, &self.service.to_string()),
+            project: deployment.project,
+            service: self.service.to_string(),
+            status: api_name(&deployment.status),
+            version: deployment.version,
+            branch: deployment.branch,
+            tier: api_name(&deployment.tier),
+            replicas: deployment.replicas,
+            regions: deployment.regions,
+            observability: deployment.observability,
+            deprecated: deployment.deprecated.unwrap_or(false),
+            error: deployment.error,
+            config: config::decode(&deployment.config.config_file),
+        };
+
+        output::print(&description)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DeploymentDescription {
+    project: String,
+    service: String,
+    status: String,
+    version: String,
+    branch: Option<String>,
+    tier: String,
+    replicas: i64,
+    regions: Vec<String>,
+    observability: bool,
+    deprecated: bool,
+    url: String,
+    error: Option<String>,
+    config: String,
+}
+
+impl Render for DeploymentDescription {
+    fn print_table(&self) {
+        println!("Project: {}", self.project);
+        println!("Version: {}", self.version);
+
+        if self.deprecated {
+            println!();
+            println!("NOTE:");
+            println!("This deployment is deprecated and immutable.");
+            println!("Please delete it and re-create. Note that this will reset the storage.");
+            println!();
         }
 
-        Ok(())
+        println!("Branch: {}", self.branch.as_deref().unwrap_or("Default"));
+        println!("Tier: {}", self.tier);
+        println!("Url: {}", self.url);
+
+        // convert config of type String to &str
+        print_config_file(&self.config);
+
+        if let Some(error) = &self.error {
+            println!("\n─────────────── ERROR INFO ───────────────");
+            println!("Error: {error}");
+            println!("\n─────────────── ERROR INFO ───────────────");
+        }
     }
 }
@@ -1,54 +1,310 @@
 #![allow(clippy::enum_variant_names)]
 
+use std::time::Duration;
+
 use anyhow::Result;
-use clap::Args;
+use chrono::{DateTime, Local, Utc};
+use clap::{Args, ValueEnum};
+use comfy_table::{Cell, Color};
+use serde::Serialize;
+use strum_macros::Display;
 
-use slot::graphql::deployments::list_deployments::{ResponseData, Variables};
+use slot::graphql::deployments::list_deployments::{
+    DeploymentStatus, DeploymentTier, ResponseData, Variables,
+};
 use slot::graphql::deployments::ListDeployments;
 use slot::graphql::GraphQLQuery;
 use slot::{api::Client, credential::Credentials};
 
+use super::services::Service;
+use super::{api_name, Tier};
+use crate::output::{self, Render};
+
 #[derive(Debug, Args)]
 #[command(next_help_heading = "List options")]
-pub struct ListArgs {}
+pub struct ListArgs {
+    #[arg(long)]
+    #[arg(help = "Only list the deployments of this team.")]
+    pub team: Option<String>,
+
+    #[arg(long, value_enum)]
+    #[arg(help = "Only list the deployments of this service.")]
+    pub service: Option<Service>,
+
+    #[arg(long, value_enum, value_delimiter = ',')]
+    #[arg(
+        help = "Only list the deployments with these statuses. Deleted deployments are \
+                  hidden unless selected."
+    )]
+    pub status: Vec<Status>,
+
+    #[arg(long, value_enum)]
+    #[arg(help = "Only list the deployments of this tier.")]
+    pub tier: Option<Tier>,
+
+    #[arg(long, value_enum, default_value_t = SortBy::Project)]
+    #[arg(
+        help = "Sort the deployments by this column. Sorting by creation date lists the \
+                  newest deployments first."
+    )]
+    pub sort: SortBy,
+
+    #[arg(short, long)]
+    #[arg(help = "Refresh the list until interrupted.")]
+    pub watch: bool,
+
+    #[arg(long, default_value = "5", requires = "watch")]
+    #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+    #[arg(help = "The number of seconds between the refreshes of --watch.")]
+    pub interval: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Status {
+    Active,
+    Disabled,
+    Error,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SortBy {
+    Project,
+    Service,
+    Status,
+    Tier,
+    Created,
+}
 
 impl ListArgs {
     pub async fn run(&self) -> Result<()> {
-        let request_body = ListDeployments::build_query(Variables {});
-
         let user = Credentials::load()?;
         let client = Client::new_with_token(user.access_token);
 
+        if !self.watch {
+            return output::print(&self.fetch(&client).await?);
+        }
+
+        loop {
+            // A failed refresh, e.g. a network hiccup, is retried on the next one.
+            match self.fetch(&client).await {
+                Ok(deployments) => {
+                    if output::is_human() {
+                        // Clear the screen and move the cursor to the top left corner.
+                        print!("\x1B[2J\x1B[1;1H");
+                        println!(
+                            "Every {}s, last refreshed at {}\n",
+                            self.interval,
+                            Local::now().format("%H:%M:%S")
+                        );
+                    }
+                    output::print_streamed(&deployments)?;
+                }
+                Err(error) => eprintln!("Failed to refresh the deployments: {error:#}"),
+            }
+
+            tokio::time::sleep(Duration::from_secs(self.interval)).await;
+        }
+    }
+
+    /// Fetches the deployments of the user's teams, filtered and sorted according to the args.
+    async fn fetch(&self, client: &Client) -> Result<Vec<DeploymentEntry>> {
+        let request_body = ListDeployments::build_query(Variables {});
         let data: ResponseData = client.query(&request_body).await?;
-        if let Some(me) = data.me {
-            if let Some(teams) = me.teams.edges {
-                let teams: Vec<_> = teams
-                    .iter()
-                    .filter_map(|team| team.as_ref())
-                    .filter_map(|team| team.node.as_ref())
-                    .collect::<_>();
-
-                let deployments: Vec<_> = teams
-                    .iter()
-                    .filter_map(|team| team.deployments.edges.as_ref())
-                    .flatten()
-                    .filter_map(|deployment| deployment.as_ref())
-                    .filter(|deployment| {
-                        deployment
-                            .node
-                            .as_ref()
-                            .is_some_and(|node| format!("{:?}", node.status) != "deleted")
-                    })
-                    .collect();
-
-                for deployment in deployments {
-                    println!("Project: {}", deployment.node.as_ref().unwrap().project);
-                    println!("Service: {}", deployment.node.as_ref().unwrap().service.id);
-                    println!("---");
+
+        let mut deployments = Vec::new();
+        let teams = data.me.and_then(|me| me.teams.edges).unwrap_or_default();
+        for team in teams.into_iter().flatten().filter_map(|team| team.node) {
+            if self.team.as_ref().is_some_and(|name| *name != team.name) {
+                continue;
+            }
+
+            let edges = team.deployments.edges.unwrap_or_default();
+            for node in edges.into_iter().flatten().filter_map(|d| d.node) {
+                let status = parse_status(node.status);
+                if !self.selects_status(status.as_ref().ok().copied()) {
+                    continue;
+                }
+                if self
+                    .service
+                    .as_ref()
+                    .is_some_and(|service| service.to_string() != node.service.id)
+                {
+                    continue;
                 }
+                if self
+                    .tier
+                    .as_ref()
+                    .is_some_and(|tier| !is_tier(tier, &node.tier))
+                {
+                    continue;
+                }
+
+                deployments.push(DeploymentEntry {
+                    team: team.name.clone(),
+                    project: node.project,
+                    service: node.service.id,
+                    status: status.map_or_else(|other| other, |status| status.to_string()),
+                    tier: api_name(&node.tier),
+                    regions: node.regions,
+                    replicas: node.replicas,
+                    version: node.version,
+                    created_at: node.created_at,
+                    spin_down_at: node.spin_down_at,
+                    error: node.error,
+                });
+            }
+        }
+
+        self.sort_deployments(&mut deployments);
+        Ok(deployments)
+    }
+
+    /// Whether a deployment with the given status is listed. Deleted deployments are only listed
+    /// when explicitly selected, and those with a status unknown to the CLI only when no status
+    /// is selected.
+    fn selects_status(&self, status: Option<Status>) -> bool {
+        match status {
+            Some(status) if self.status.is_empty() => status != Status::Deleted,
+            Some(status) => self.status.contains(&status),
+            None => self.status.is_empty(),
+        }
+    }
+
+    fn sort_deployments(&self, deployments: &mut [DeploymentEntry]) {
+        let by_name = |a: &DeploymentEntry, b: &DeploymentEntry| {
+            (&a.project, &a.service).cmp(&(&b.project, &b.service))
+        };
+
+        match self.sort {
+            SortBy::Project => deployments.sort_by(by_name),
+            SortBy::Service => {
+                deployments.sort_by(|a, b| a.service.cmp(&b.service).then_with(|| by_name(a, b)))
             }
+            SortBy::Status => {
+                deployments.sort_by(|a, b| a.status.cmp(&b.status).then_with(|| by_name(a, b)))
+            }
+            SortBy::Tier => deployments.sort_by(|a, b| {
+                tier_rank(&a.tier)
+                    .cmp(&tier_rank(&b.tier))
+                    .then_with(|| by_name(a, b))
+            }),
+            SortBy::Created => deployments.sort_by(|a, b| {
+                parse_time(&b.created_at)
+                    .cmp(&parse_time(&a.created_at))
+                    .then_with(|| by_name(a, b))
+            }),
+        }
+    }
+}
+
+/// The tiers from the smallest to the largest, including the deprecated ones.
+const TIERS: [&str; 6] = ["basic", "common", "pro", "epic", "legendary", "insane"];
+
+/// Returns the status of a deployment, or its name if it is unknown to this version of the CLI.
+fn parse_status(status: DeploymentStatus) -> Result<Status, String> {
+    match status {
+        DeploymentStatus::active => Ok(Status::Active),
+        DeploymentStatus::disabled => Ok(Status::Disabled),
+        DeploymentStatus::error => Ok(Status::Error),
+        DeploymentStatus::deleted => Ok(Status::Deleted),
+        DeploymentStatus::Other(other) => Err(other),
+    }
+}
+
+fn is_tier(tier: &Tier, deployment: &DeploymentTier) -> bool {
+    matches!(
+        (tier, deployment),
+        (Tier::Basic, DeploymentTier::basic)
+            | (Tier::Pro, DeploymentTier::pro)
+            | (Tier::Epic, DeploymentTier::epic)
+            | (Tier::Legendary, DeploymentTier::legendary)
+            | (Tier::Insane, DeploymentTier::insane)
+    )
+}
+
+/// Returns the rank of a tier, unknown tiers being ranked last.
+fn tier_rank(tier: &str) -> usize {
+    TIERS.iter().position(|t| *t == tier).unwrap_or(TIERS.len())
+}
+
+fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn format_time(time: &str) -> String {
+    parse_time(time)
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| time.to_string())
+}
+
+#[derive(Debug, Serialize)]
+struct DeploymentEntry {
+    team: String,
+    project: String,
+    service: String,
+    status: String,
+    tier: String,
+    regions: Vec<String>,
+    replicas: i64,
+    version: String,
+    created_at: String,
+    spin_down_at: Option<String>,
+    error: Option<String>,
+}
+
+impl Render for Vec<DeploymentEntry> {
+    fn print_table(&self) {
+        if self.is_empty() {
+            println!("No deployments found.");
+            return;
+        }
+
+        let mut table = output::table(vec![
+            "Team",
+            "Project",
+            "Service",
+            "Status",
+            "Tier",
+            "Regions",
+            "Replicas",
+            "Version",
+            "Created",
+            "Spin Down",
+            "Error",
+        ]);
+
+        for deployment in self {
+            let status_color = match Status::from_str(&deployment.status, false) {
+                Ok(Status::Active) => Color::Green,
+                Ok(Status::Disabled) => Color::Yellow,
+                Ok(Status::Error) => Color::Red,
+                Ok(Status::Deleted) | Err(_) => Color::DarkGrey,
+            };
+
+            table.add_row(vec![
+                Cell::new(&deployment.team),
+                Cell::new(&deployment.project),
+                Cell::new(&deployment.service),
+                Cell::new(&deployment.status).fg(status_color),
+                Cell::new(&deployment.tier),
+                Cell::new(deployment.regions.join(", ")),
+                Cell::new(deployment.replicas),
+                Cell::new(&deployment.version),
+                Cell::new(format_time(&deployment.created_at)),
+                Cell::new(
+                    deployment
+                        .spin_down_at
+                        .as_deref()
+                        .map_or("-".to_string(), format_time),
+                ),
+                Cell::new(deployment.error.as_deref().unwrap_or("-")),
+            ]);
         }
 
-        Ok(())
+        println!("{table}");
     }
 }
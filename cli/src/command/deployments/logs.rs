@@ -10,12 +10,14 @@ use std::{
 // use tokio::selectV
 use anyhow::Result;
 use clap::Args;
+use serde::Serialize;
 use slot::credential::Credentials;
 use slot::graphql::deployments::deployment_logs::DeploymentService;
 use slot::graphql::{deployments::deployment_logs::*, GraphQLQuery};
 use slot::{api::Client, graphql::deployments::DeploymentLogs};
 
 use super::services::Service;
+use crate::output::{self, Render};
 
 #[derive(Debug, Args)]
 #[command(next_help_heading = "Deployment logs options")]
@@ -55,7 +57,7 @@ impl LogsArgs {
             let logs = reader
                 .query(self.since.clone(), self.limit, self.container.clone())
                 .await?;
-            println!("{}", logs.content);
+            output::print(&Logs::from(logs))?;
         }
 
         Ok(())
@@ -113,24 +115,46 @@ impl LogReader {
         })
         .expect("Error setting Ctrl-C handler");
 
-        let mut logs = self.query(since, 1, container.clone()).await?;
+        let logs = self.query(since, 1, container.clone()).await?;
         let mut printed_logs = HashSet::new();
 
         let mut since = logs.until;
         while running.load(Ordering::SeqCst) {
             tokio::time::sleep(Duration::from_millis(1000)).await;
-            logs = self
+            let logs = self
                 .query(Some(since.clone()), 25, container.clone())
                 .await?;
 
+            since = logs.until.clone();
+
             if !printed_logs.contains(&logs.content) {
-                println!("{}", logs.content);
                 printed_logs.insert(logs.content.clone()); // Add the log to the buffer
+                output::print_streamed(&Logs::from(logs))?;
             }
-
-            since = logs.until
         }
 
         Ok(())
     }
 }
+
+#[derive(Debug, Serialize)]
+struct Logs {
+    lines: Vec<String>,
+    /// The RFC3339 timestamp of the last log, to pass to `--since` to fetch the next ones.
+    until: String,
+}
+
+impl From<DeploymentLogsDeploymentLogs> for Logs {
+    fn from(logs: DeploymentLogsDeploymentLogs) -> Self {
+        Self {
+            lines: logs.content.lines().map(str::to_string).collect(),
+            until: logs.until,
+        }
+    }
+}
+
+impl Render for Logs {
+    fn print_table(&self) {
+        println!("{}", self.lines.join("\n"));
+    }
+}
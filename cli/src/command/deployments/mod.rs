@@ -1,11 +1,18 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Subcommand;
 use colored::*;
+use serde::Serialize;
+use slot::api::Client;
+use slot::config;
+use slot::graphql::deployments::describe_deployment::{self, DeploymentService};
+use slot::graphql::deployments::DescribeDeployment;
+use slot::graphql::GraphQLQuery;
+use slot::manifest::{Change, DeploymentState, Field, Service};
 use strum_macros::Display;
 
 use self::{
     accounts::AccountsArgs, create::CreateArgs, delete::DeleteArgs, describe::DescribeArgs,
-    list::ListArgs, logs::LogsArgs, update::UpdateArgs,
+    list::ListArgs, logs::LogsArgs, update::UpdateArgs, validate::ValidateArgs,
 };
 use crate::command::deployments::transfer::TransferArgs;
 
@@ -18,6 +25,7 @@ mod logs;
 mod services;
 mod transfer;
 mod update;
+pub(crate) mod validate;
 
 pub const CARTRIDGE_BASE_URL: &str = "https://api.cartridge.gg/x";
 
@@ -46,6 +54,9 @@ pub enum Deployments {
 
     #[command(about = "Fetch Katana accounts.")]
     Accounts(AccountsArgs),
+
+    #[command(about = "Validate the configuration of a deployment without deploying it.")]
+    Validate(ValidateArgs),
 }
 
 impl Deployments {
@@ -59,8 +70,16 @@ impl Deployments {
             Deployments::Transfer(args) => args.run().await,
             Deployments::Logs(args) => args.run().await,
             Deployments::Accounts(args) => args.run().await,
+            Deployments::Validate(args) => args.run().await,
         }
     }
+
+    pub fn renders_output(&self) -> bool {
+        matches!(
+            self,
+            Deployments::Describe(_) | Deployments::List(_) | Deployments::Logs(_)
+        )
+    }
 }
 
 #[derive(clap::ValueEnum, Clone, Debug, serde::Serialize, PartialEq, Eq, Hash, Display)]
@@ -119,6 +138,16 @@ pub(crate) fn pretty_print_toml(str: &str) {
     }
 }
 
+/// Returns the name of a deployment tier or status as the API names it, e.g. `pro`, including the
+/// values unknown to this version of the CLI. Each query generates its own copy of these enums,
+/// which all serialize to that name.
+pub(crate) fn api_name(value: &impl Serialize) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => unreachable!("the enums of the API serialize to their name"),
+    }
+}
+
 /// Prints observability secret information with Prometheus and Grafana URLs.
 pub(crate) fn print_observability_secret(secret: &str, project: &str, service: &str) {
     let base_url = service_url(project, service);
@@ -128,3 +157,83 @@ pub(crate) fn print_observability_secret(secret: &str, project: &str, service: &
     println!("\nPrometheus URL: {}/prometheus", base_url);
     println!("Grafana URL: {}/grafana", base_url);
 }
+
+/// Fetches the current state of a deployment.
+pub(crate) async fn fetch_state(
+    client: &Client,
+    project: &str,
+    service: Service,
+) -> Result<DeploymentState> {
+    let request_body = DescribeDeployment::build_query(describe_deployment::Variables {
+        project: project.to_string(),
+        service: match service {
+            Service::Katana => DeploymentService::katana,
+            Service::Torii => DeploymentService::torii,
+        },
+    });
+
+    let data: describe_deployment::ResponseData = client.query(&request_body).await?;
+    let deployment = data
+        .deployment
+        .with_context(|| format!("Deployment {project}/{service} not found"))?;
+
+    Ok(DeploymentState {
+        tier: api_name(&deployment.tier),
+        regions: deployment.regions,
+        version: deployment.version,
+        replicas: deployment.replicas,
+        observability: deployment.observability,
+        config: config::decode(&deployment.config.config_file),
+    })
+}
+
+/// Whether applying the changes to a deployment resets its storage.
+pub(crate) fn resets_storage(service: Service, changes: &[Change]) -> bool {
+    changes
+        .iter()
+        .filter(|change| change.field == Field::Config)
+        .any(|change| {
+            config::diff(&change.from, &change.to).is_ok_and(|config_changes| {
+                config_changes
+                    .iter()
+                    .any(|config_change| config_change.resets_storage(service))
+            })
+        })
+}
+
+/// Prints the changes to a deployment, comparing the configuration key by key.
+pub(crate) fn print_changes(service: Service, changes: &[Change]) {
+    for change in changes {
+        if change.field != Field::Config {
+            let note = if change.field.is_updatable() {
+                String::new()
+            } else {
+                " (requires re-creating the deployment)".red().to_string()
+            };
+            println!(
+                "    {}: {} -> {}{note}",
+                change.field, change.from, change.to
+            );
+            continue;
+        }
+
+        let Ok(config_changes) = config::diff(&change.from, &change.to) else {
+            println!("    config: replaced");
+            continue;
+        };
+
+        for config_change in config_changes {
+            let line = match &config_change {
+                config::ConfigChange::Added { .. } => config_change.to_string().green(),
+                config::ConfigChange::Removed { .. } => config_change.to_string().red(),
+                config::ConfigChange::Changed { .. } => config_change.to_string().yellow(),
+            };
+
+            if config_change.resets_storage(service) {
+                println!("    {line}{}", " (resets storage)".red());
+            } else {
+                println!("    {line}");
+            }
+        }
+    }
+}
@@ -41,6 +41,16 @@ impl KatanaCreateArgs {
         }
         Ok(())
     }
+
+    pub fn options(&self) -> slot::config::KatanaOptions {
+        slot::config::KatanaOptions {
+            provable: self.provable,
+            network: self.network.clone(),
+            saya: self.saya,
+            optimistic: self.optimistic,
+            fork_provider_url: self.fork_provider_url.clone(),
+        }
+    }
 }
 
 /// Update a Katana deployment.
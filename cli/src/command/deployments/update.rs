@@ -2,13 +2,18 @@
 
 use super::services::UpdateServiceCommands;
 use crate::command::deployments::Tier;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Args;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::Confirm;
 use slot::api::Client;
+use slot::config;
 use slot::credential::Credentials;
 use slot::graphql::deployments::update_deployment::{self, UpdateServiceInput};
 use slot::graphql::deployments::{update_deployment::*, UpdateDeployment};
 use slot::graphql::GraphQLQuery;
+use slot::manifest::{Change, DeploymentState, Field, Service};
+use slot::version;
 
 #[derive(Debug, Args)]
 #[command(next_help_heading = "Update options")]
@@ -25,47 +30,101 @@ pub struct UpdateArgs {
     #[arg(help = "Enable observability for monitoring and metrics.")]
     pub observability: Option<bool>,
 
+    #[arg(long)]
+    #[arg(help = "Show the changes to the deployment without applying them.")]
+    pub dry_run: bool,
+
+    #[arg(long)]
+    #[arg(help = "Show the changes to the deployment before applying them.")]
+    pub diff: bool,
+
+    #[arg(short, long)]
+    #[arg(help = "Apply changes resetting the storage without asking for confirmation.")]
+    pub force: bool,
+
     #[command(subcommand)]
     update_commands: UpdateServiceCommands,
 }
 
 impl UpdateArgs {
     pub async fn run(&self) -> Result<()> {
+        // Read the raw config file content
+        let config_path = match &self.update_commands {
+            UpdateServiceCommands::Katana(args) => args.config.as_ref(),
+            UpdateServiceCommands::Torii(args) => args.config.as_ref(),
+        };
+        let new_config = config_path.map(std::fs::read_to_string).transpose()?;
+
         let service = match &self.update_commands {
-            UpdateServiceCommands::Katana(args) => {
-                let config = if let Some(config) = args.config.clone() {
-                    // Read the raw config file content
-                    let service_config = std::fs::read_to_string(&config)?;
-                    Some(slot::read::base64_encode_string(&service_config))
-                } else {
-                    None
-                };
-
-                UpdateServiceInput {
-                    type_: DeploymentService::katana,
-                    version: None,
-                    config,
-                    torii: None,
+            UpdateServiceCommands::Katana(_) => Service::Katana,
+            UpdateServiceCommands::Torii(_) => Service::Torii,
+        };
+        super::validate::check_config(service, config_path.map(|p| p.as_path()))?;
+
+        let user = Credentials::load()?;
+        let client = Client::new_with_token(user.access_token);
+
+        // The storage reset by a new config is always checked, the changes are only shown on demand
+        let mut resets_storage = false;
+        if new_config.is_some() || self.dry_run || self.diff {
+            let current = super::fetch_state(&client, &self.project, service).await?;
+            let changes = self.changes(&current, new_config.as_deref());
+            resets_storage = super::resets_storage(service, &changes);
+
+            if self.dry_run || self.diff {
+                if changes.is_empty() {
+                    println!("No changes to {}/{service}.", self.project);
+                    return Ok(());
                 }
-            }
-            UpdateServiceCommands::Torii(args) => {
-                let config = if let Some(config) = args.config.clone() {
-                    // Read the raw config file content
-                    let service_config = std::fs::read_to_string(&config)?;
-                    Some(slot::read::base64_encode_string(&service_config))
-                } else {
-                    None
-                };
-
-                UpdateServiceInput {
-                    type_: DeploymentService::torii,
-                    version: args.version.clone(),
-                    config,
-                    torii: Some(ToriiUpdateInput {
-                        replicas: args.replicas,
-                    }),
+
+                println!("Changes to {}/{service}:", self.project);
+                super::print_changes(service, &changes);
+
+                if self.dry_run {
+                    return Ok(());
                 }
             }
+        }
+
+        if resets_storage && !self.force {
+            if !version::is_interactive() {
+                bail!(
+                    "The update resets the storage of the deployment, use `--force` to apply it or \
+                     `--diff` to show the changes"
+                );
+            }
+
+            let confirmation = Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(
+                    "The update resets the storage of the deployment. Do you want to proceed?",
+                )
+                .default(false)
+                .show_default(true)
+                .wait_for_newline(true)
+                .interact()?;
+
+            if !confirmation {
+                return Ok(());
+            }
+        }
+
+        let config = new_config.as_deref().map(slot::read::base64_encode_string);
+
+        let service = match &self.update_commands {
+            UpdateServiceCommands::Katana(_) => UpdateServiceInput {
+                type_: DeploymentService::katana,
+                version: None,
+                config,
+                torii: None,
+            },
+            UpdateServiceCommands::Torii(args) => UpdateServiceInput {
+                type_: DeploymentService::torii,
+                version: args.version.clone(),
+                config,
+                torii: Some(ToriiUpdateInput {
+                    replicas: args.replicas,
+                }),
+            },
         };
 
         let tier = match &self.tier {
@@ -85,9 +144,6 @@ impl UpdateArgs {
             observability: self.observability,
         });
 
-        let user = Credentials::load()?;
-        let client = Client::new_with_token(user.access_token);
-
         let response: update_deployment::ResponseData = client.query(&request_body).await?;
 
         let service = match &self.update_commands {
@@ -121,4 +177,55 @@ impl UpdateArgs {
 
         Ok(())
     }
+
+    /// Returns the changes made by the update to the current state of the deployment.
+    fn changes(&self, current: &DeploymentState, new_config: Option<&str>) -> Vec<Change> {
+        let mut changes = Vec::new();
+        let mut change = |field, from: String, to: String| {
+            changes.push(Change { field, from, to });
+        };
+
+        if let Some(tier) = &self.tier {
+            let tier = tier.to_string().to_lowercase();
+            if tier != current.tier {
+                change(Field::Tier, current.tier.clone(), tier);
+            }
+        }
+
+        if let UpdateServiceCommands::Torii(args) = &self.update_commands {
+            if let Some(version) = &args.version {
+                if !version::is_same_version(&current.version, version) {
+                    change(Field::Version, current.version.clone(), version.clone());
+                }
+            }
+
+            if let Some(replicas) = args.replicas {
+                if replicas != current.replicas {
+                    change(
+                        Field::Replicas,
+                        current.replicas.to_string(),
+                        replicas.to_string(),
+                    );
+                }
+            }
+        }
+
+        if let Some(observability) = self.observability {
+            if observability != current.observability {
+                change(
+                    Field::Observability,
+                    current.observability.to_string(),
+                    observability.to_string(),
+                );
+            }
+        }
+
+        if let Some(config) = new_config {
+            if !config::same_config(&current.config, config) {
+                change(Field::Config, current.config.clone(), config.to_string());
+            }
+        }
+
+        changes
+    }
 }
@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use clap::Args;
+use colored::*;
+use slot::config::{self, KatanaOptions, Severity};
+use slot::manifest::Service;
+
+use super::services::CreateServiceCommands;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Validate options")]
+pub struct ValidateArgs {
+    #[command(subcommand)]
+    validate_commands: CreateServiceCommands,
+}
+
+impl ValidateArgs {
+    pub async fn run(&self) -> Result<()> {
+        let service = match &self.validate_commands {
+            CreateServiceCommands::Katana(args) => {
+                args.validate()?;
+                check_katana(args.config.as_deref(), &args.options())?;
+                Service::Katana
+            }
+            CreateServiceCommands::Torii(args) => {
+                check_config(Service::Torii, Some(args.config.as_path()))?;
+                Service::Torii
+            }
+        };
+
+        println!("The {service} configuration is valid.");
+        Ok(())
+    }
+}
+
+/// Validates the configuration file of a service before deploying it, printing the issues found.
+/// Fails if any of them prevents the deployment.
+pub(crate) fn check_config(service: Service, path: Option<&Path>) -> Result<()> {
+    let Some(path) = path else {
+        return Ok(());
+    };
+
+    let config = std::fs::read_to_string(path)?;
+    report(config::validate(service, &config)?)
+}
+
+/// Validates the configuration file and the options of a Katana deployment before creating it.
+pub(crate) fn check_katana(path: Option<&Path>, options: &KatanaOptions) -> Result<()> {
+    let config = path.map(std::fs::read_to_string).transpose()?;
+
+    let mut issues = match &config {
+        Some(config) => config::validate(Service::Katana, config)?,
+        None => Vec::new(),
+    };
+    issues.extend(config::validate_katana_options(options, config.as_deref()));
+
+    report(issues)
+}
+
+fn report(issues: Vec<config::Issue>) -> Result<()> {
+    let mut errors = 0;
+    for issue in &issues {
+        match issue.severity {
+            Severity::Warning => eprintln!("{}", issue.to_string().yellow()),
+            Severity::Error => {
+                errors += 1;
+                eprintln!("{}", issue.to_string().red());
+            }
+        }
+    }
+
+    if errors > 0 {
+        bail!("Invalid configuration, found {errors} error(s)");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use anyhow::{bail, Result};
+use clap::Args;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::Confirm;
+use slot::api::Client;
+use slot::credential::Credentials;
+use slot::graphql::deployments::{create_deployment, update_deployment};
+use slot::graphql::deployments::{CreateDeployment, UpdateDeployment};
+use slot::graphql::GraphQLQuery;
+use slot::manifest::{Action, Change, Field, Service};
+use slot::version;
+
+use super::{ManifestArgs, PlannedService};
+use crate::command::deployments::{service_url, validate};
+
+#[derive(Debug, Args)]
+pub struct ApplyArgs {
+    #[command(flatten)]
+    manifest: ManifestArgs,
+
+    #[arg(short, long)]
+    #[arg(help = "Apply the changes without asking for confirmation.")]
+    yes: bool,
+}
+
+impl ApplyArgs {
+    pub async fn run(&self) -> Result<()> {
+        let user = Credentials::load()?;
+        let client = Client::new_with_token(user.access_token);
+
+        let (manifest, planned) = self.manifest.plan(&client).await?;
+        super::print_plan(&planned);
+
+        if planned.iter().all(|s| s.action == Action::Unchanged) {
+            println!("\nNothing to apply.");
+            return Ok(());
+        }
+
+        for service in &planned {
+            let config = service.manifest.config.as_deref();
+            match (&service.action, service.service) {
+                (Action::Create, Service::Katana) => {
+                    validate::check_katana(config, &service.manifest.katana_options())?
+                }
+                (Action::Unchanged, _) => {}
+                _ => validate::check_config(service.service, config)?,
+            }
+
+            if let Action::Update(changes) = &service.action {
+                if let Some(change) = changes.iter().find(|c| !c.field.is_updatable()) {
+                    bail!(
+                        "The {} of {}/{} can't be updated, delete the deployment to re-create it",
+                        change.field,
+                        service.project,
+                        service.service
+                    );
+                }
+            }
+        }
+
+        if !self.yes {
+            if !version::is_interactive() {
+                bail!("Refusing to apply the changes without confirmation, use `--yes`");
+            }
+
+            let confirmation = Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt("Do you want to apply these changes?")
+                .default(false)
+                .show_default(true)
+                .wait_for_newline(true)
+                .interact()?;
+
+            if !confirmation {
+                return Ok(());
+            }
+        }
+
+        for service in &planned {
+            match &service.action {
+                Action::Create => create(&client, manifest.team.clone(), service).await?,
+                Action::Update(changes) => update(&client, service, changes).await?,
+                Action::Unchanged => {}
+            }
+        }
+
+        println!("\nApply success 🚀");
+
+        Ok(())
+    }
+}
+
+async fn create(client: &Client, team: Option<String>, service: &PlannedService) -> Result<()> {
+    use create_deployment::*;
+
+    let manifest = &service.manifest;
+    let config = service
+        .config
+        .as_deref()
+        .map(slot::read::base64_encode_string)
+        .unwrap_or_default();
+
+    let input = match service.service {
+        Service::Katana => CreateServiceInput {
+            type_: DeploymentService::katana,
+            version: manifest.version.clone(),
+            config,
+            katana: Some(KatanaCreateInput {
+                provable: Some(manifest.provable),
+                network: manifest.network.clone(),
+                saya: Some(manifest.saya),
+                optimistic: Some(manifest.optimistic),
+                fork_provider_url: manifest.fork_provider_url.clone(),
+            }),
+            torii: None,
+        },
+        Service::Torii => CreateServiceInput {
+            type_: DeploymentService::torii,
+            version: manifest.version.clone(),
+            config,
+            katana: None,
+            torii: Some(ToriiCreateInput {
+                replicas: manifest.replicas,
+                replication: manifest.replication.then_some(true),
+            }),
+        },
+    };
+
+    let request_body = CreateDeployment::build_query(Variables {
+        project: service.project.clone(),
+        service: input,
+        tier: manifest.tier.unwrap_or_default().into(),
+        wait: Some(true),
+        regions: manifest.regions.clone(),
+        team,
+        observability: manifest.observability,
+    });
+
+    println!(
+        "\nDeploying {} ...",
+        service_url(&service.project, &service.service.to_string())
+    );
+    let _: ResponseData = client.query(&request_body).await?;
+
+    Ok(())
+}
+
+async fn update(client: &Client, service: &PlannedService, changes: &[Change]) -> Result<()> {
+    use update_deployment::*;
+
+    let manifest = &service.manifest;
+    let changed = |field: Field| changes.iter().any(|c| c.field == field);
+
+    let config = if changed(Field::Config) {
+        service
+            .config
+            .as_deref()
+            .map(slot::read::base64_encode_string)
+    } else {
+        None
+    };
+    let version = if changed(Field::Version) {
+        manifest.version.clone()
+    } else {
+        None
+    };
+
+    let input = match service.service {
+        Service::Katana => UpdateServiceInput {
+            type_: DeploymentService::katana,
+            version,
+            config,
+            torii: None,
+        },
+        Service::Torii => UpdateServiceInput {
+            type_: DeploymentService::torii,
+            version,
+            config,
+            torii: Some(ToriiUpdateInput {
+                replicas: if changed(Field::Replicas) {
+                    manifest.replicas
+                } else {
+                    None
+                },
+            }),
+        },
+    };
+
+    let request_body = UpdateDeployment::build_query(Variables {
+        project: service.project.clone(),
+        service: input,
+        tier: manifest
+            .tier
+            .filter(|_| changed(Field::Tier))
+            .map(Into::into),
+        wait: Some(true),
+        observability: if changed(Field::Observability) {
+            manifest.observability
+        } else {
+            None
+        },
+    });
+
+    println!(
+        "\nUpdating {} ...",
+        service_url(&service.project, &service.service.to_string())
+    );
+    let _: ResponseData = client.query(&request_body).await?;
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::Args;
+use colored::*;
+use slot::api::Client;
+use slot::graphql::deployments::list_deployments::{self, DeploymentStatus};
+use slot::graphql::deployments::ListDeployments;
+use slot::graphql::GraphQLQuery;
+use slot::manifest::{self, Action, Manifest, Service, ServiceManifest};
+
+use crate::command::deployments;
+
+pub mod apply;
+pub mod plan;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Manifest options")]
+pub struct ManifestArgs {
+    #[arg(short, long, value_name = "PATH")]
+    #[arg(default_value = manifest::MANIFEST_FILE_NAME)]
+    #[arg(help = "The path of the project manifest.")]
+    pub manifest: PathBuf,
+}
+
+/// A service declared in the manifest, with what has to be done for it to be deployed.
+#[derive(Debug)]
+pub(crate) struct PlannedService {
+    pub project: String,
+    pub service: Service,
+    pub manifest: ServiceManifest,
+    /// The contents of the configuration file.
+    pub config: Option<String>,
+    pub action: Action,
+}
+
+impl ManifestArgs {
+    /// Loads the manifest and compares it to the existing deployments.
+    pub(crate) async fn plan(&self, client: &Client) -> Result<(Manifest, Vec<PlannedService>)> {
+        let manifest = Manifest::load(&self.manifest)?;
+        let existing = existing_deployments(client).await?;
+
+        let mut planned = Vec::new();
+        for (project, service, service_manifest) in manifest.services() {
+            let config = service_manifest.read_config()?;
+
+            let current = match existing.get(&(project.to_string(), service.to_string())) {
+                Some(team) if manifest.team.as_ref().is_some_and(|owner| owner != team) => bail!(
+                    "Deployment {project}/{service} belongs to the team '{team}', not to the team \
+                     of the manifest"
+                ),
+                Some(_) => Some(deployments::fetch_state(client, project, service).await?),
+                None => None,
+            };
+
+            let action = manifest::plan(service_manifest, config.as_deref(), current.as_ref());
+            planned.push(PlannedService {
+                project: project.to_string(),
+                service,
+                manifest: service_manifest.clone(),
+                config,
+                action,
+            });
+        }
+
+        Ok((manifest, planned))
+    }
+}
+
+/// Returns the team owning each deployment of the user's teams, by project and service.
+async fn existing_deployments(client: &Client) -> Result<HashMap<(String, String), String>> {
+    let request_body = ListDeployments::build_query(list_deployments::Variables {});
+    let data: list_deployments::ResponseData = client.query(&request_body).await?;
+
+    let mut deployments = HashMap::new();
+    let teams = data.me.and_then(|me| me.teams.edges).unwrap_or_default();
+    for team in teams.into_iter().flatten().filter_map(|team| team.node) {
+        let edges = team.deployments.edges.unwrap_or_default();
+        for node in edges.into_iter().flatten().filter_map(|edge| edge.node) {
+            if !matches!(node.status, DeploymentStatus::deleted) {
+                deployments.insert((node.project, node.service.id), team.name.clone());
+            }
+        }
+    }
+
+    Ok(deployments)
+}
+
+/// Prints what has to be done for the deployments to match the manifest.
+pub(crate) fn print_plan(planned: &[PlannedService]) {
+    let (mut create, mut update, mut unchanged) = (0, 0, 0);
+
+    for service in planned {
+        let name = format!("{}/{}", service.project, service.service);
+        match &service.action {
+            Action::Create => {
+                create += 1;
+                println!(
+                    "{} {name} (tier {})",
+                    "+".green(),
+                    service.manifest.tier.unwrap_or_default()
+                );
+            }
+            Action::Update(changes) => {
+                update += 1;
+                println!("{} {name}", "~".yellow());
+                deployments::print_changes(service.service, changes);
+            }
+            Action::Unchanged => {
+                unchanged += 1;
+                println!("{} {name}", "=".bright_black());
+            }
+        }
+    }
+
+    println!("\nPlan: {create} to create, {update} to update, {unchanged} unchanged.");
+}
@@ -0,0 +1,24 @@
+use anyhow::Result;
+use clap::Args;
+use slot::api::Client;
+use slot::credential::Credentials;
+
+use super::ManifestArgs;
+
+#[derive(Debug, Args)]
+pub struct PlanArgs {
+    #[command(flatten)]
+    manifest: ManifestArgs,
+}
+
+impl PlanArgs {
+    pub async fn run(&self) -> Result<()> {
+        let user = Credentials::load()?;
+        let client = Client::new_with_token(user.access_token);
+
+        let (_, planned) = self.manifest.plan(&client).await?;
+        super::print_plan(&planned);
+
+        Ok(())
+    }
+}
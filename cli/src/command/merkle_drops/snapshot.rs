@@ -98,7 +98,7 @@ pub struct SnapshotArgs {
         help = "Output file path for the snapshot JSON data",
         default_value = "snapshot.json"
     )]
-    output: PathBuf,
+    output_file: PathBuf,
 
     #[arg(
         long,
@@ -245,9 +245,9 @@ impl SnapshotArgs {
 
         // Write to output file
         let output_str = serde_json::to_string_pretty(&output_data)?;
-        std::fs::write(&self.output, output_str)?;
+        std::fs::write(&self.output_file, output_str)?;
 
-        println!("Snapshot data written to: {}", self.output.display());
+        println!("Snapshot data written to: {}", self.output_file.display());
         println!("\nSummary:");
         println!("  Total unique holders: {}", sorted_holders.len());
         println!("  Total supply: {}", total_supply);
@@ -272,12 +272,12 @@ impl SnapshotArgs {
             println!("  Claims: All data per address in index 0 (no splitting)");
         }
 
-        println!("  Output file: {}", self.output.display());
+        println!("  Output file: {}", self.output_file.display());
         println!("\nNext steps:");
         println!("1. Review the generated snapshot data");
         println!(
             "2. Use 'slot merkle-drops create --json-file {}' to create a merkle drop from this snapshot",
-            self.output.display()
+            self.output_file.display()
         );
 
         Ok(())
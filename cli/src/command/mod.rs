@@ -1,23 +1,31 @@
 pub mod auth;
 pub mod deployments;
+pub mod manifest;
 pub mod merkle_drops;
 pub mod paymaster;
 pub mod paymasters;
 pub mod rpc;
+pub mod self_cmd;
+pub mod sessions;
 pub mod teams;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Subcommand;
 use slot::version;
 
 use auth::Auth;
 use deployments::Deployments;
+use manifest::{apply::ApplyArgs, plan::PlanArgs};
 use merkle_drops::MerkleDropsCmd;
 use paymaster::PaymasterCmd;
 use paymasters::PaymastersCmd;
 use rpc::RpcCmd;
+use self_cmd::SelfCmd;
+use sessions::SessionsCmd;
 use teams::Teams;
 
+use crate::output;
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Command {
@@ -46,12 +54,38 @@ pub enum Command {
     #[command(subcommand)]
     #[command(about = "Manage RPC tokens and configurations.", aliases = ["r"])]
     Rpc(RpcCmd),
+
+    #[command(subcommand)]
+    #[command(about = "Manage the sessions of the authenticated user.", aliases = ["s"])]
+    Sessions(SessionsCmd),
+
+    #[command(
+        about = "Show the changes needed for the deployments to match the slot.toml manifest."
+    )]
+    Plan(PlanArgs),
+
+    #[command(about = "Create or update the deployments declared in the slot.toml manifest.")]
+    Apply(ApplyArgs),
+
+    #[command(subcommand, name = "self")]
+    #[command(about = "Manage the installation of the Slot CLI.")]
+    SelfCmd(SelfCmd),
 }
 
 impl Command {
     pub async fn run(&self) -> Result<()> {
-        // Check for new version and run auto-update if available
-        version::check_and_auto_update();
+        if !output::is_human() && !self.renders_output() {
+            bail!(
+                "--output {} is not supported by this command.",
+                output::format()
+            );
+        }
+
+        // Check for new version and run auto-update if available, unless the installation is
+        // being managed explicitly
+        if !matches!(self, Command::SelfCmd(_)) {
+            version::check_and_auto_update().await;
+        }
 
         // Run the actual command
         match &self {
@@ -62,6 +96,28 @@ impl Command {
             Command::Paymasters(cmd) => cmd.run().await,
             Command::Paymaster(cmd) => cmd.run().await,
             Command::Rpc(cmd) => cmd.run().await,
+            Command::Sessions(cmd) => cmd.run().await,
+            Command::Plan(args) => args.run().await,
+            Command::Apply(args) => args.run().await,
+            Command::SelfCmd(cmd) => cmd.run().await,
+        }
+    }
+
+    /// Whether the command prints its result with [`output::print`], and so supports all the
+    /// output formats. The other commands only print text for humans.
+    fn renders_output(&self) -> bool {
+        match self {
+            Command::Auth(cmd) => cmd.renders_output(),
+            Command::Deployments(cmd) => cmd.renders_output(),
+            Command::Teams(cmd) => cmd.renders_output(),
+            Command::Paymasters(_) => true,
+            Command::Paymaster(cmd) => cmd.renders_output(),
+            Command::Rpc(cmd) => cmd.renders_output(),
+            Command::Sessions(cmd) => cmd.renders_output(),
+            Command::MerkleDrops(_)
+            | Command::Plan(_)
+            | Command::Apply(_)
+            | Command::SelfCmd(_) => false,
         }
     }
 }
@@ -1,5 +1,6 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Args;
+use serde::Serialize;
 use slot::api::Client;
 use slot::credential::Credentials;
 use slot::graphql::paymaster::paymaster_info;
@@ -7,6 +8,8 @@ use slot::graphql::paymaster::paymaster_info::PaymasterBudgetFeeUnit;
 use slot::graphql::paymaster::PaymasterInfo;
 use slot::graphql::GraphQLQuery;
 
+use crate::output::{self, Render};
+
 #[derive(Debug, Args)]
 #[command(next_help_heading = "Paymaster info options")]
 pub struct InfoArgs {}
@@ -22,116 +25,138 @@ impl InfoArgs {
 
         let data: paymaster_info::ResponseData = client.query(&request_body).await?;
 
-        match data.paymaster {
-            Some(paymaster) => {
-                // Format budget with 2 decimal places by dividing by 1e6
-                let budget_formatted = paymaster.budget as f64 / 1e6;
-                let strk_fees_formatted = paymaster.strk_fees as f64 / 1e6;
-                let credit_fees_formatted = paymaster.credit_fees as f64 / 1e6;
-
-                // Calculate usage percentage and create progress bar
-                let spent_amount = match paymaster.budget_fee_unit {
-                    PaymasterBudgetFeeUnit::STRK => strk_fees_formatted,
-                    PaymasterBudgetFeeUnit::CREDIT => credit_fees_formatted,
-                    _ => 0.0,
-                };
-
-                let usage_percentage = if budget_formatted > 0.0 {
-                    (spent_amount / budget_formatted * 100.0).min(100.0)
-                } else {
-                    0.0
-                };
-
-                // Create progress bar (40 characters wide)
-                let bar_width = 30;
-                let filled_width = (usage_percentage / 100.0 * bar_width as f64) as usize;
-                let progress_bar = format!(
-                    "[{}{}]",
-                    "█".repeat(filled_width),
-                    "░".repeat(bar_width - filled_width)
-                );
-
-                println!("\n🔍 Paymaster Info for '{}'", name);
-                println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-
-                println!("🏢 Details:");
-                println!(
-                    "  • Team: {}",
-                    paymaster
-                        .team
-                        .as_ref()
-                        .map(|t| t.name.as_str())
-                        .unwrap_or("Unknown")
-                );
-                println!(
-                    "  • Active: {}",
-                    if paymaster.active {
-                        "✅ Yes"
-                    } else {
-                        "❌ No"
-                    }
-                );
-
-                println!("\n💰 Budget:");
-                let usd_equivalent = match paymaster.budget_fee_unit {
-                    PaymasterBudgetFeeUnit::CREDIT => budget_formatted * 0.01, // 100 credit = 1 USD
-                    _ => 0.0,
-                };
-
-                if usd_equivalent > 0.0 {
-                    println!("  • Total: ${:.2} USD", usd_equivalent);
-                } else {
-                    println!("  • Total: NONE (Please Top Up)");
-                }
-
-                // Only display the relevant fee type based on budget unit
-                match paymaster.budget_fee_unit {
-                    PaymasterBudgetFeeUnit::STRK => {
-                        println!("  • Spent: {:.2} STRK", strk_fees_formatted);
-                    }
-                    PaymasterBudgetFeeUnit::CREDIT => {
-                        let spent_usd_equivalent = credit_fees_formatted * 0.01; // 100 credit = 1 USD
-                        println!("  • Spent: ${:.2} USD", spent_usd_equivalent);
-                    }
-                    _ => {}
-                }
-
-                // Display usage progress bar
-                if budget_formatted > 0.0 {
-                    println!("  • Usage: {} {:.1}%", progress_bar, usage_percentage);
-                }
-
-                if paymaster.legacy_strk_fees > 0 || paymaster.legacy_eth_fees > 0 {
-                    let legacy_strk_formatted = paymaster.legacy_strk_fees as f64 / 1e6;
-                    let legacy_eth_formatted = paymaster.legacy_eth_fees as f64 / 1e6;
-                    println!("\n💸 Outstanding Balance:");
-                    println!("  • This is the balance due prior to self service migration.");
-                    if paymaster.legacy_strk_fees > 0 {
-                        println!("  • Spent STRK: {:.2}", legacy_strk_formatted);
-                    }
-
-                    if paymaster.legacy_eth_fees > 0 {
-                        println!("  • Spent ETH: {:.4}", legacy_eth_formatted);
-                    }
-                }
-
-                println!("\n🧾 Lifetime Transactions:");
-                let total_successful =
-                    paymaster.successful_transactions + paymaster.legacy_successful_transactions;
-                let total_reverted =
-                    paymaster.reverted_transactions + paymaster.legacy_reverted_transactions;
-                println!("  • Total: {}", total_successful + total_reverted);
-                println!("  • Successful: {}", total_successful);
-                println!("  • Reverted: {}", total_reverted);
-
-                println!("\n📋 Policies:");
-                println!("  • Count: {}", paymaster.policies.total_count);
+        let Some(paymaster) = data.paymaster else {
+            bail!("Paymaster '{}' not found", name);
+        };
+
+        // Format budget with 2 decimal places by dividing by 1e6
+        let budget_formatted = paymaster.budget as f64 / 1e6;
+        let strk_fees_formatted = paymaster.strk_fees as f64 / 1e6;
+        let credit_fees_formatted = paymaster.credit_fees as f64 / 1e6;
+
+        // Calculate usage percentage
+        let spent_amount = match paymaster.budget_fee_unit {
+            PaymasterBudgetFeeUnit::STRK => strk_fees_formatted,
+            PaymasterBudgetFeeUnit::CREDIT => credit_fees_formatted,
+            _ => 0.0,
+        };
+
+        let usage_percentage = if budget_formatted > 0.0 {
+            (spent_amount / budget_formatted * 100.0).min(100.0)
+        } else {
+            0.0
+        };
+
+        // 100 credit = 1 USD
+        let (budget, spent, budget_unit) = match paymaster.budget_fee_unit {
+            PaymasterBudgetFeeUnit::CREDIT => {
+                (budget_formatted * 0.01, credit_fees_formatted * 0.01, "USD")
             }
-            None => {
-                println!("Paymaster '{}' not found", name);
+            PaymasterBudgetFeeUnit::STRK => (budget_formatted, strk_fees_formatted, "STRK"),
+            _ => (budget_formatted, spent_amount, "UNKNOWN"),
+        };
+
+        let successful_transactions =
+            paymaster.successful_transactions + paymaster.legacy_successful_transactions;
+        let reverted_transactions =
+            paymaster.reverted_transactions + paymaster.legacy_reverted_transactions;
+
+        output::print(&PaymasterDetails {
+            name,
+            team: paymaster.team.map(|team| team.name),
+            active: paymaster.active,
+            budget,
+            spent,
+            budget_unit: budget_unit.to_string(),
+            usage_percentage,
+            legacy_strk_fees: paymaster.legacy_strk_fees as f64 / 1e6,
+            legacy_eth_fees: paymaster.legacy_eth_fees as f64 / 1e6,
+            total_transactions: successful_transactions + reverted_transactions,
+            successful_transactions,
+            reverted_transactions,
+            policies: paymaster.policies.total_count,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PaymasterDetails {
+    name: String,
+    team: Option<String>,
+    active: bool,
+    budget: f64,
+    spent: f64,
+    /// The unit of the budget and of the spent amount, `USD` or `STRK`.
+    budget_unit: String,
+    usage_percentage: f64,
+    /// The fees due prior to the self service migration.
+    legacy_strk_fees: f64,
+    legacy_eth_fees: f64,
+    total_transactions: i64,
+    successful_transactions: i64,
+    reverted_transactions: i64,
+    /// The number of policies.
+    policies: i64,
+}
+
+impl Render for PaymasterDetails {
+    fn print_table(&self) {
+        // Create progress bar (30 characters wide)
+        let bar_width = 30;
+        let filled_width = (self.usage_percentage / 100.0 * bar_width as f64) as usize;
+        let progress_bar = format!(
+            "[{}{}]",
+            "█".repeat(filled_width),
+            "░".repeat(bar_width - filled_width)
+        );
+
+        println!("\n🔍 Paymaster Info for '{}'", self.name);
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+        println!("🏢 Details:");
+        println!("  • Team: {}", self.team.as_deref().unwrap_or("Unknown"));
+        println!(
+            "  • Active: {}",
+            if self.active { "✅ Yes" } else { "❌ No" }
+        );
+
+        println!("\n💰 Budget:");
+        if self.budget_unit == "USD" && self.budget > 0.0 {
+            println!("  • Total: ${:.2} USD", self.budget);
+        } else {
+            println!("  • Total: NONE (Please Top Up)");
+        }
+
+        // Only display the relevant fee type based on budget unit
+        match self.budget_unit.as_str() {
+            "STRK" => println!("  • Spent: {:.2} STRK", self.spent),
+            "USD" => println!("  • Spent: ${:.2} USD", self.spent),
+            _ => {}
+        }
+
+        // Display usage progress bar
+        if self.budget > 0.0 {
+            println!("  • Usage: {} {:.1}%", progress_bar, self.usage_percentage);
+        }
+
+        if self.legacy_strk_fees > 0.0 || self.legacy_eth_fees > 0.0 {
+            println!("\n💸 Outstanding Balance:");
+            println!("  • This is the balance due prior to self service migration.");
+            if self.legacy_strk_fees > 0.0 {
+                println!("  • Spent STRK: {:.2}", self.legacy_strk_fees);
+            }
+
+            if self.legacy_eth_fees > 0.0 {
+                println!("  • Spent ETH: {:.4}", self.legacy_eth_fees);
             }
         }
 
-        Ok(())
+        println!("\n🧾 Lifetime Transactions:");
+        println!("  • Total: {}", self.total_transactions);
+        println!("  • Successful: {}", self.successful_transactions);
+        println!("  • Reverted: {}", self.reverted_transactions);
+
+        println!("\n📋 Policies:");
+        println!("  • Count: {}", self.policies);
     }
 }
@@ -98,6 +98,16 @@ impl PaymasterCmd {
             PaymasterSubcommand::Dune(cmd) => cmd.run(self.name.clone()).await,
         }
     }
+
+    pub fn renders_output(&self) -> bool {
+        match &self.command {
+            PaymasterSubcommand::Policy(cmd) => cmd.renders_output(),
+            PaymasterSubcommand::Stats(_)
+            | PaymasterSubcommand::Info(_)
+            | PaymasterSubcommand::Transactions(_) => true,
+            _ => false,
+        }
+    }
 }
 
 pub fn print_policies_table(policies: &[PolicyArgs]) {
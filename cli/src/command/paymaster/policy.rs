@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
+use serde::Serialize;
 use slot::api::Client;
 use slot::credential::Credentials;
 use slot::graphql::paymaster::add_policies::{
@@ -15,6 +16,7 @@ use std::io::{self, Write};
 use std::path::PathBuf;
 
 use super::PolicyArgs;
+use crate::output::{self, Render};
 
 const WARN_ENTRYPOINTS: &[&str] = &["transfer", "approve"];
 
@@ -379,40 +381,53 @@ impl PolicyCmd {
         // 4. Execute Query
         let data: list_policies::ResponseData = client.query(&request_body).await?;
 
-        // 5. Print Result (using Debug format as workaround for Serialize issue)
-        match data.paymaster {
-            Some(paymaster) => {
-                let policies_list: Vec<_> = paymaster
-                    .policies
-                    .edges
-                    .into_iter()
-                    .flatten()
-                    .filter_map(|edge| edge.unwrap().node)
-                    .filter(|policy| policy.active)
-                    .collect();
-
-                if policies_list.is_empty() {
-                    println!("No policies found for paymaster '{}'.", name);
-                    return Ok(());
-                }
-
-                let policy_args: Vec<PolicyArgs> = policies_list
-                    .iter()
-                    .map(|p| PolicyArgs {
-                        contract: p.contract_address.clone(),
-                        entrypoint: p.entry_point.clone(),
-                        trigger_contract: p.trigger.as_ref().map(|t| t.contract_address.clone()),
-                        trigger_entrypoint: p.trigger.as_ref().map(|t| t.entry_point.clone()),
-                    })
-                    .collect();
-
-                super::print_policies_table(&policy_args);
-            }
-            None => {
-                println!("Paymaster '{}' not found.", name);
-            }
+        // 5. Print Result
+        let Some(paymaster) = data.paymaster else {
+            anyhow::bail!("Paymaster '{}' not found.", name);
+        };
+
+        let policies = paymaster
+            .policies
+            .edges
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.unwrap().node)
+            .filter(|policy| policy.active)
+            .map(|p| PolicyArgs {
+                contract: p.contract_address,
+                entrypoint: p.entry_point,
+                trigger_contract: p.trigger.as_ref().map(|t| t.contract_address.clone()),
+                trigger_entrypoint: p.trigger.map(|t| t.entry_point),
+            })
+            .collect();
+
+        output::print(&PolicyList {
+            paymaster: name,
+            policies,
+        })
+    }
+
+    pub fn renders_output(&self) -> bool {
+        matches!(self.command, PolicySubcommand::List(_))
+    }
+}
+
+/// The active policies of a paymaster.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct PolicyList {
+    #[serde(skip)]
+    paymaster: String,
+    policies: Vec<PolicyArgs>,
+}
+
+impl Render for PolicyList {
+    fn print_table(&self) {
+        if self.policies.is_empty() {
+            println!("No policies found for paymaster '{}'.", self.paymaster);
+            return;
         }
 
-        Ok(())
+        super::print_policies_table(&self.policies);
     }
 }
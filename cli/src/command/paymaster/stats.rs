@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use clap::Args;
+use serde::Serialize;
 use slot::api::Client;
 use slot::credential::Credentials;
 use slot::graphql::paymaster::paymaster_stats;
@@ -9,6 +10,7 @@ use slot::graphql::GraphQLQuery;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::utils;
+use crate::output::{self, Render};
 
 #[derive(Debug, Args)]
 #[command(next_help_heading = "Paymaster stats options")]
@@ -56,39 +58,79 @@ impl StatsArgs {
         let data: paymaster_stats::ResponseData = client.query(&request_body).await?;
 
         // 8. Print Results
-        let stats = &data.paymaster_stats;
+        let stats = data.paymaster_stats;
+
+        // Calculate the success rate and TPS
+        let (success_rate, tps) = if stats.total_transactions > 0 {
+            let total = stats.total_transactions as f64;
+            (
+                Some(stats.successful_transactions as f64 / total * 100.0),
+                Some(total / duration.as_secs() as f64),
+            )
+        } else {
+            (None, None)
+        };
+
+        output::print(&Stats {
+            paymaster: name,
+            period: self.last.clone(),
+            total_transactions: stats.total_transactions,
+            successful_transactions: stats.successful_transactions,
+            reverted_transactions: stats.reverted_transactions,
+            success_rate,
+            tps,
+            total_usd_fees: stats.total_usd_fees.unwrap_or(0.0),
+            avg_usd_fee: stats.avg_usd_fee.unwrap_or(0.0),
+            min_usd_fee: stats.min_usd_fee.unwrap_or(0.0),
+            max_usd_fee: stats.max_usd_fee.unwrap_or(0.0),
+            unique_users: stats.unique_users,
+        })?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Stats {
+    paymaster: String,
+    period: String,
+    total_transactions: i64,
+    successful_transactions: i64,
+    reverted_transactions: i64,
+    /// The percentage of successful transactions, if there are any.
+    success_rate: Option<f64>,
+    tps: Option<f64>,
+    total_usd_fees: f64,
+    avg_usd_fee: f64,
+    min_usd_fee: f64,
+    max_usd_fee: f64,
+    unique_users: i64,
+}
 
-        println!("\n📊 Paymaster Stats for '{}' (Last {})", name, self.last);
+impl Render for Stats {
+    fn print_table(&self) {
+        println!(
+            "\n📊 Paymaster Stats for '{}' (Last {})",
+            self.paymaster, self.period
+        );
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
         println!("📈 Transactions:");
-        println!("  • Total: {}", stats.total_transactions);
-        println!("  • Successful: {}", stats.successful_transactions);
-        println!("  • Reverted: {}", stats.reverted_transactions);
+        println!("  • Total: {}", self.total_transactions);
+        println!("  • Successful: {}", self.successful_transactions);
+        println!("  • Reverted: {}", self.reverted_transactions);
 
-        if stats.total_transactions > 0 {
-            let success_rate =
-                (stats.successful_transactions as f64 / stats.total_transactions as f64) * 100.0;
+        if let (Some(success_rate), Some(tps)) = (self.success_rate, self.tps) {
             println!("  • Success Rate: {:.1}%", success_rate);
-
-            // Calculate TPS
-            let duration_seconds = duration.as_secs() as f64;
-            let tps = stats.total_transactions as f64 / duration_seconds;
             println!("  • TPS: {:.4}", tps);
         }
 
         println!("\n💰 Fees (USD):");
-        println!(
-            "  • Total ({}): ${:.2}",
-            self.last,
-            stats.total_usd_fees.unwrap_or(0.0)
-        );
-        println!("  • Average: ${:.6}", stats.avg_usd_fee.unwrap_or(0.0));
-        println!("  • Minimum: ${:.6}", stats.min_usd_fee.unwrap_or(0.0));
-        println!("  • Maximum: ${:.6}", stats.max_usd_fee.unwrap_or(0.0));
+        println!("  • Total ({}): ${:.2}", self.period, self.total_usd_fees);
+        println!("  • Average: ${:.6}", self.avg_usd_fee);
+        println!("  • Minimum: ${:.6}", self.min_usd_fee);
+        println!("  • Maximum: ${:.6}", self.max_usd_fee);
 
         println!("\n👥 Users:");
-        println!("  • Unique Users: {}", stats.unique_users);
-
-        Ok(())
+        println!("  • Unique Users: {}", self.unique_users);
     }
 }
@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
 use clap::Args;
+use serde::Serialize;
 use slot::api::Client;
 use slot::credential::Credentials;
 use slot::graphql::paymaster::paymaster_transactions;
@@ -12,6 +13,7 @@ use slot::graphql::GraphQLQuery;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::utils;
+use crate::output::{self, Render};
 
 #[derive(Debug, Args)]
 #[command(next_help_heading = "Transaction query options")]
@@ -108,42 +110,26 @@ impl TransactionArgs {
         let client = Client::new_with_token(credentials.access_token);
         let data: paymaster_transactions::ResponseData = client.query(&request_body).await?;
 
-        let transactions = data.paymaster_transactions;
-
-        if transactions.is_empty() {
-            println!(
-                "\n📊 Paymaster Transactions for '{}' (Last {})",
-                paymaster_name, self.last
-            );
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!("No transactions found.");
-            return Ok(());
-        }
-
-        // Convert to display format
-        // Convert to display format
-        let display_transactions: Vec<TransactionDisplay> = transactions
+        let transactions = data
+            .paymaster_transactions
             .into_iter()
-            .map(|t| TransactionDisplay {
-                transaction_hash: t.transaction_hash.clone(),
-                executed_at: format_relative_time(&t.executed_at),
+            .map(|t| Transaction {
+                transaction_hash: t.transaction_hash,
+                executed_at: t.executed_at,
                 status: match t.status {
                     paymaster_transactions::ActivityStatus::COMPLETED => "SUCCESS".to_string(),
                     paymaster_transactions::ActivityStatus::FAILED => "REVERTED".to_string(),
                     _ => format!("{:?}", t.status), // Fallback for any other status values
                 },
-                usd_fee: format!("${:.4}", t.usd_fee),
+                usd_fee: t.usd_fee,
             })
             .collect();
 
-        // Print header with time reference like stats.rs
-        println!(
-            "\n📊 Paymaster Transactions for '{}' (Last {})",
-            paymaster_name, self.last
-        );
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-
-        print_transactions_table(&display_transactions);
+        output::print(&Transactions {
+            paymaster: paymaster_name,
+            period: self.last.clone(),
+            transactions,
+        })?;
 
         Ok(())
     }
@@ -211,3 +197,52 @@ pub fn print_transactions_table(transactions: &[TransactionDisplay]) {
         );
     }
 }
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct Transactions {
+    #[serde(skip)]
+    paymaster: String,
+    #[serde(skip)]
+    period: String,
+    transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Serialize)]
+struct Transaction {
+    transaction_hash: String,
+    /// The RFC3339 timestamp of the execution.
+    executed_at: String,
+    status: String,
+    usd_fee: f64,
+}
+
+impl Render for Transactions {
+    fn print_table(&self) {
+        // Print header with time reference like stats.rs
+        println!(
+            "\n📊 Paymaster Transactions for '{}' (Last {})",
+            self.paymaster, self.period
+        );
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+        if self.transactions.is_empty() {
+            println!("No transactions found.");
+            return;
+        }
+
+        // Convert to display format
+        let display_transactions: Vec<TransactionDisplay> = self
+            .transactions
+            .iter()
+            .map(|t| TransactionDisplay {
+                transaction_hash: t.transaction_hash.clone(),
+                executed_at: format_relative_time(&t.executed_at),
+                status: t.status.clone(),
+                usd_fee: format!("${:.4}", t.usd_fee),
+            })
+            .collect();
+
+        print_transactions_table(&display_transactions);
+    }
+}
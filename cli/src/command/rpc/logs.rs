@@ -1,6 +1,7 @@
 use anyhow::Result;
 use clap::Args;
-use comfy_table::{presets::UTF8_FULL, Cell, ContentArrangement, Table};
+use comfy_table::Cell;
+use serde::Serialize;
 use serde_json::json;
 use slot::api::Client;
 use slot::credential::Credentials;
@@ -8,6 +9,7 @@ use slot::graphql::rpc::list_rpc_logs::ResponseData;
 use std::time::SystemTime;
 
 use crate::command::paymaster::utils::parse_duration;
+use crate::output::{self, Render};
 
 #[derive(Debug, Args)]
 #[command(next_help_heading = "List RPC logs options")]
@@ -38,10 +40,10 @@ impl LogsArgs {
     pub async fn run(&self) -> Result<()> {
         // Validate limit is within bounds
         let limit = if self.limit > 50 {
-            println!("Warning: Limit exceeds maximum of 50. Using 50 instead.");
+            eprintln!("Warning: Limit exceeds maximum of 50. Using 50 instead.");
             50
         } else if self.limit < 1 {
-            println!("Warning: Limit must be at least 1. Using 1 instead.");
+            eprintln!("Warning: Limit must be at least 1. Using 1 instead.");
             1
         } else {
             self.limit
@@ -112,67 +114,111 @@ impl LogsArgs {
 
         let data: ResponseData = client.query(&request_body).await?;
 
-        if let Some(connection) = data.rpc_logs {
-            if let Some(edges) = connection.edges {
-                let logs: Vec<_> = edges
-                    .iter()
-                    .filter_map(|edge| edge.as_ref())
-                    .filter_map(|edge| edge.node.as_ref())
-                    .collect();
-
-                if logs.is_empty() {
-                    println!("\nNo RPC logs found for team '{}'", self.team);
-                    return Ok(());
-                }
+        let Some(connection) = data.rpc_logs else {
+            return Ok(());
+        };
 
-                let mut table = Table::new();
-                table
-                    .load_preset(UTF8_FULL)
-                    .set_content_arrangement(ContentArrangement::Dynamic)
-                    .set_header(vec![
-                        Cell::new("Timestamp"),
-                        Cell::new("Network"),
-                        Cell::new("Method"),
-                        Cell::new("Status"),
-                        Cell::new("Duration (ms)"),
-                        Cell::new("Size (bytes)"),
-                        Cell::new("API Key ID"),
-                        Cell::new("CORS Domain ID"),
-                        Cell::new("Client IP"),
-                    ]);
-
-                for log in logs {
-                    table.add_row(vec![
-                        Cell::new(&log.timestamp),
-                        Cell::new(format!("{:?}", log.network)),
-                        Cell::new(log.method.as_ref().map_or("-", |s| s.as_str())),
-                        Cell::new(log.response_status.to_string()),
-                        Cell::new(log.duration_ms.to_string()),
-                        Cell::new(log.response_size_bytes.to_string()),
-                        Cell::new(log.api_key_id.as_deref().unwrap_or("-")),
-                        Cell::new(log.cors_domain_id.as_deref().unwrap_or("-")),
-                        Cell::new(&log.client_ip),
-                    ]);
-                }
+        let logs = connection
+            .edges
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.node)
+            .map(|log| RpcLog {
+                timestamp: log.timestamp,
+                network: format!("{:?}", log.network),
+                method: log.method,
+                response_status: log.response_status,
+                duration_ms: log.duration_ms,
+                response_size_bytes: log.response_size_bytes,
+                api_key_id: log.api_key_id,
+                cors_domain_id: log.cors_domain_id,
+                client_ip: log.client_ip,
+            })
+            .collect();
+
+        output::print(&RpcLogs {
+            team: self.team.clone(),
+            logs,
+            total_count: connection.total_count,
+            has_next_page: connection.page_info.has_next_page,
+            end_cursor: connection.page_info.end_cursor,
+        })?;
 
-                println!("\nRPC Logs for team '{}':", self.team);
-                println!("{table}");
-
-                // Show pagination info if available
-                let page_info = connection.page_info;
-                if page_info.has_next_page {
-                    if let Some(end_cursor) = page_info.end_cursor {
-                        println!(
-                            "\nMore logs available. Use --after {} to see next page",
-                            end_cursor
-                        );
-                    }
-                }
+        Ok(())
+    }
+}
 
-                println!("\nTotal logs: {}", connection.total_count);
+#[derive(Debug, Serialize)]
+struct RpcLogs {
+    #[serde(skip)]
+    team: String,
+    logs: Vec<RpcLog>,
+    total_count: i64,
+    has_next_page: bool,
+    /// The cursor to pass to `--after` to fetch the next page.
+    end_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcLog {
+    timestamp: String,
+    network: String,
+    method: Option<String>,
+    response_status: i64,
+    duration_ms: i64,
+    response_size_bytes: i64,
+    api_key_id: Option<String>,
+    cors_domain_id: Option<String>,
+    client_ip: String,
+}
+
+impl Render for RpcLogs {
+    fn print_table(&self) {
+        if self.logs.is_empty() {
+            println!("\nNo RPC logs found for team '{}'", self.team);
+            return;
+        }
+
+        let mut table = output::table(vec![
+            Cell::new("Timestamp"),
+            Cell::new("Network"),
+            Cell::new("Method"),
+            Cell::new("Status"),
+            Cell::new("Duration (ms)"),
+            Cell::new("Size (bytes)"),
+            Cell::new("API Key ID"),
+            Cell::new("CORS Domain ID"),
+            Cell::new("Client IP"),
+        ]);
+
+        for log in &self.logs {
+            table.add_row(vec![
+                Cell::new(&log.timestamp),
+                Cell::new(&log.network),
+                Cell::new(log.method.as_deref().unwrap_or("-")),
+                Cell::new(log.response_status.to_string()),
+                Cell::new(log.duration_ms.to_string()),
+                Cell::new(log.response_size_bytes.to_string()),
+                Cell::new(log.api_key_id.as_deref().unwrap_or("-")),
+                Cell::new(log.cors_domain_id.as_deref().unwrap_or("-")),
+                Cell::new(&log.client_ip),
+            ]);
+        }
+
+        println!("\nRPC Logs for team '{}':", self.team);
+        println!("{table}");
+
+        // Show pagination info if available
+        if self.has_next_page {
+            if let Some(end_cursor) = &self.end_cursor {
+                println!(
+                    "\nMore logs available. Use --after {} to see next page",
+                    end_cursor
+                );
             }
         }
 
-        Ok(())
+        println!("\nTotal logs: {}", self.total_count);
     }
 }
@@ -31,4 +31,12 @@ impl RpcCmd {
             RpcCmd::Logs(args) => args.run().await,
         }
     }
+
+    pub fn renders_output(&self) -> bool {
+        match self {
+            RpcCmd::Tokens(cmd) => cmd.renders_output(),
+            RpcCmd::Whitelist(cmd) => cmd.renders_output(),
+            RpcCmd::Logs(_) => true,
+        }
+    }
 }
@@ -37,4 +37,8 @@ impl TokensCmd {
             TokensSubcommand::List(args) => args.run().await,
         }
     }
+
+    pub fn renders_output(&self) -> bool {
+        matches!(self.command, TokensSubcommand::List(_))
+    }
 }
@@ -1,12 +1,15 @@
 use anyhow::Result;
 use clap::Args;
-use comfy_table::{presets::UTF8_FULL, Cell, ContentArrangement, Table};
+use comfy_table::Cell;
+use serde::Serialize;
 use slot::api::Client;
 use slot::credential::Credentials;
 use slot::graphql::rpc::list_rpc_api_keys::{ResponseData, Variables};
 use slot::graphql::rpc::ListRpcApiKeys;
 use slot::graphql::GraphQLQuery;
 
+use crate::output::{self, Render};
+
 #[derive(Debug, Args)]
 #[command(next_help_heading = "List RPC tokens options")]
 pub struct ListArgs {
@@ -28,48 +31,78 @@ impl ListArgs {
 
         let data: ResponseData = client.query(&request_body).await?;
 
-        if let Some(connection) = data.rpc_api_keys {
-            if let Some(edges) = connection.edges {
-                let tokens: Vec<_> = edges
-                    .iter()
-                    .filter_map(|edge| edge.as_ref())
-                    .filter_map(|edge| edge.node.as_ref())
-                    .collect();
+        let tokens = data
+            .rpc_api_keys
+            .and_then(|connection| connection.edges)
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.node)
+            .map(|token| RpcToken {
+                id: token.id,
+                name: token.name,
+                key_prefix: token.key_prefix,
+                active: token.active,
+                created_at: token.created_at,
+                last_used_at: token.last_used_at,
+            })
+            .collect();
+
+        output::print(&RpcTokens {
+            team: self.team.clone(),
+            tokens,
+        })?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct RpcTokens {
+    #[serde(skip)]
+    team: String,
+    tokens: Vec<RpcToken>,
+}
 
-                if tokens.is_empty() {
-                    println!("\nNo RPC API keys found for team '{}'", self.team);
-                    return Ok(());
-                }
+#[derive(Debug, Serialize)]
+struct RpcToken {
+    id: String,
+    name: String,
+    key_prefix: String,
+    active: bool,
+    created_at: String,
+    last_used_at: Option<String>,
+}
 
-                let mut table = Table::new();
-                table
-                    .load_preset(UTF8_FULL)
-                    .set_content_arrangement(ContentArrangement::Dynamic)
-                    .set_header(vec![
-                        Cell::new("ID"),
-                        Cell::new("Name"),
-                        Cell::new("Key Prefix"),
-                        Cell::new("Active"),
-                        Cell::new("Created At"),
-                        Cell::new("Last Used"),
-                    ]);
+impl Render for RpcTokens {
+    fn print_table(&self) {
+        if self.tokens.is_empty() {
+            println!("\nNo RPC API keys found for team '{}'", self.team);
+            return;
+        }
 
-                for token in tokens {
-                    table.add_row(vec![
-                        Cell::new(&token.id),
-                        Cell::new(&token.name),
-                        Cell::new(&token.key_prefix),
-                        Cell::new(if token.active { "✓" } else { "✗" }),
-                        Cell::new(&token.created_at),
-                        Cell::new(token.last_used_at.as_ref().map_or("-", |s| s.as_str())),
-                    ]);
-                }
+        let mut table = output::table(vec![
+            Cell::new("ID"),
+            Cell::new("Name"),
+            Cell::new("Key Prefix"),
+            Cell::new("Active"),
+            Cell::new("Created At"),
+            Cell::new("Last Used"),
+        ]);
 
-                println!("\nRPC API Keys for team '{}':", self.team);
-                println!("{table}");
-            }
+        for token in &self.tokens {
+            table.add_row(vec![
+                Cell::new(&token.id),
+                Cell::new(&token.name),
+                Cell::new(&token.key_prefix),
+                Cell::new(if token.active { "✓" } else { "✗" }),
+                Cell::new(&token.created_at),
+                Cell::new(token.last_used_at.as_deref().unwrap_or("-")),
+            ]);
         }
 
-        Ok(())
+        println!("\nRPC API Keys for team '{}':", self.team);
+        println!("{table}");
     }
 }
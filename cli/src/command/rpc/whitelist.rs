@@ -37,4 +37,8 @@ impl WhitelistCmd {
             WhitelistSubcommand::List(args) => args.run().await,
         }
     }
+
+    pub fn renders_output(&self) -> bool {
+        matches!(self.command, WhitelistSubcommand::List(_))
+    }
 }
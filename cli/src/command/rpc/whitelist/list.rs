@@ -1,12 +1,15 @@
 use anyhow::Result;
 use clap::Args;
-use comfy_table::{presets::UTF8_FULL, Cell, ContentArrangement, Table};
+use comfy_table::Cell;
+use serde::Serialize;
 use slot::api::Client;
 use slot::credential::Credentials;
 use slot::graphql::rpc::list_rpc_cors_domains::{ResponseData, Variables};
 use slot::graphql::rpc::ListRpcCorsDomains;
 use slot::graphql::GraphQLQuery;
 
+use crate::output::{self, Render};
+
 #[derive(Debug, Args)]
 #[command(next_help_heading = "List whitelist origins options")]
 pub struct ListArgs {
@@ -28,42 +31,66 @@ impl ListArgs {
 
         let data: ResponseData = client.query(&request_body).await?;
 
-        if let Some(connection) = data.rpc_cors_domains {
-            if let Some(edges) = connection.edges {
-                let domains: Vec<_> = edges
-                    .iter()
-                    .filter_map(|edge| edge.as_ref())
-                    .filter_map(|edge| edge.node.as_ref())
-                    .collect();
+        let domains = data
+            .rpc_cors_domains
+            .and_then(|connection| connection.edges)
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.node)
+            .map(|domain| CorsDomain {
+                id: domain.id,
+                domain: domain.domain,
+                created_at: domain.created_at,
+            })
+            .collect();
+
+        output::print(&CorsDomains {
+            team: self.team.clone(),
+            domains,
+        })?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct CorsDomains {
+    #[serde(skip)]
+    team: String,
+    domains: Vec<CorsDomain>,
+}
 
-                if domains.is_empty() {
-                    println!("\nNo CORS domains found for team '{}'", self.team);
-                    return Ok(());
-                }
+#[derive(Debug, Serialize)]
+struct CorsDomain {
+    id: String,
+    domain: String,
+    created_at: String,
+}
 
-                let mut table = Table::new();
-                table
-                    .load_preset(UTF8_FULL)
-                    .set_content_arrangement(ContentArrangement::Dynamic)
-                    .set_header(vec![
-                        Cell::new("ID"),
-                        Cell::new("Domain"),
-                        Cell::new("Created At"),
-                    ]);
+impl Render for CorsDomains {
+    fn print_table(&self) {
+        if self.domains.is_empty() {
+            println!("\nNo CORS domains found for team '{}'", self.team);
+            return;
+        }
 
-                for domain in domains {
-                    table.add_row(vec![
-                        Cell::new(&domain.id),
-                        Cell::new(&domain.domain),
-                        Cell::new(&domain.created_at),
-                    ]);
-                }
+        let mut table = output::table(vec![
+            Cell::new("ID"),
+            Cell::new("Domain"),
+            Cell::new("Created At"),
+        ]);
 
-                println!("\nCORS Domains for team '{}':", self.team);
-                println!("{table}");
-            }
+        for domain in &self.domains {
+            table.add_row(vec![
+                Cell::new(&domain.id),
+                Cell::new(&domain.domain),
+                Cell::new(&domain.created_at),
+            ]);
         }
 
-        Ok(())
+        println!("\nCORS Domains for team '{}':", self.team);
+        println!("{table}");
     }
 }
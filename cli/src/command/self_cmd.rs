@@ -0,0 +1,76 @@
+use std::env;
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use slot::version;
+
+/// Command group for managing the installation of the Slot CLI
+#[derive(Subcommand, Debug)]
+pub enum SelfCmd {
+    #[command(about = "Update the Slot CLI, to the latest or the pinned version.")]
+    Update(UpdateArgs),
+
+    #[command(about = "Roll back to the version the Slot CLI was last updated from.")]
+    Rollback,
+
+    #[command(about = "Pin the version of the Slot CLI used in the current directory.")]
+    Pin(PinArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct UpdateArgs {
+    #[arg(long)]
+    #[arg(value_name = "VERSION")]
+    #[arg(help = "The version to install, instead of the latest or the pinned one.")]
+    version: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct PinArgs {
+    #[arg(value_name = "VERSION")]
+    #[arg(help = "The version to pin. Defaults to the running version.")]
+    version: Option<String>,
+}
+
+impl SelfCmd {
+    pub async fn run(&self) -> Result<()> {
+        match &self {
+            SelfCmd::Update(args) => {
+                let current = env!("CARGO_PKG_VERSION");
+
+                let version = match (&args.version, version::pinned_version()) {
+                    (Some(version), _) => version.clone(),
+                    (None, Some(pinned)) if version::is_same_version(&pinned, current) => {
+                        println!("Slot CLI is already at the pinned version {current}.");
+                        return Ok(());
+                    }
+                    (None, Some(pinned)) => pinned,
+                    // An explicit update always checks again, instead of using the cached check.
+                    (None, None) => {
+                        match tokio::task::spawn_blocking(version::fetch_latest_version).await?? {
+                            Some(latest) => latest,
+                            None => {
+                                println!("Slot CLI is already up to date.");
+                                return Ok(());
+                            }
+                        }
+                    }
+                };
+
+                version::update(&version).await?;
+                println!("Slot CLI updated to {version}. Run `slot self rollback` to undo.");
+            }
+            SelfCmd::Rollback => {
+                version::rollback()?;
+                println!("Slot CLI rolled back to the version it was updated from.");
+            }
+            SelfCmd::Pin(args) => {
+                let pinned = args.version.as_deref().unwrap_or(env!("CARGO_PKG_VERSION"));
+                let path = version::pin_version(&env::current_dir()?, pinned)?;
+                println!("Pinned Slot CLI to {pinned} in {}.", path.display());
+            }
+        }
+
+        Ok(())
+    }
+}
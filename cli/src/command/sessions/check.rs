@@ -0,0 +1,91 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Args;
+use slot::session;
+use starknet::core::types::{Call, Felt};
+use starknet::core::utils::get_selector_from_name;
+
+use super::SessionArgs;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Check options")]
+pub struct CheckArgs {
+    #[command(flatten)]
+    session: SessionArgs,
+
+    #[arg(long = "call", required = true)]
+    #[arg(value_name = "CONTRACT,ENTRYPOINT[,CALLDATA...]")]
+    #[arg(value_parser = parse_call)]
+    #[arg(help = "A call of the multicall to check. Can be repeated.")]
+    calls: Vec<CheckedCall>,
+}
+
+/// A call to check, along with the name of its entrypoint for display.
+#[derive(Debug, Clone)]
+struct CheckedCall {
+    entrypoint: String,
+    call: Call,
+}
+
+impl CheckArgs {
+    pub async fn run(&self) -> Result<()> {
+        let session = self.session.load()?;
+        if session.is_expired() {
+            println!("Warning: the session has expired.");
+        }
+
+        let calls = self
+            .calls
+            .iter()
+            .map(|c| c.call.clone())
+            .collect::<Vec<_>>();
+        let checks = session::check_calls(&session, &calls);
+
+        for (checked, check) in self.calls.iter().zip(&checks) {
+            let status = if check.coverage.is_allowed() {
+                "ok"
+            } else {
+                "denied"
+            };
+            println!(
+                "[{status}] {:#x} {}: {}",
+                check.call.to, checked.entrypoint, check.coverage
+            );
+        }
+
+        let denied = checks.iter().filter(|c| !c.coverage.is_allowed()).count();
+        if denied > 0 {
+            bail!(
+                "{denied} of {} calls are not allowed by the session.",
+                checks.len()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_call(value: &str) -> Result<CheckedCall> {
+    let mut parts = value.split(',');
+
+    let to = parts.next().ok_or(anyhow!("missing contract"))?;
+    let to = Felt::from_str(to)?;
+    let entrypoint = parts
+        .next()
+        .ok_or(anyhow!("missing entrypoint"))?
+        .to_owned();
+    ensure!(!entrypoint.is_empty(), "missing entrypoint");
+
+    let calldata = parts.map(Felt::from_str).collect::<Result<Vec<_>, _>>()?;
+    let selector = get_selector_from_name(&entrypoint)?;
+
+    Ok(CheckedCall {
+        entrypoint,
+        call: Call {
+            to,
+            selector,
+            calldata,
+        },
+    })
+}
@@ -0,0 +1,44 @@
+use anyhow::Result;
+use clap::Args;
+use slot::session;
+use starknet::core::types::{Call, Felt};
+use starknet::core::utils::get_selector_from_name;
+
+use super::SessionArgs;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Execute options")]
+pub struct ExecArgs {
+    #[command(flatten)]
+    session: SessionArgs,
+
+    #[arg(long)]
+    #[arg(value_name = "ADDRESS")]
+    #[arg(help = "The address of the contract to call.")]
+    contract: Felt,
+
+    #[arg(long)]
+    #[arg(value_name = "NAME")]
+    #[arg(help = "The name of the contract method to call.")]
+    entrypoint: String,
+
+    #[arg(long, value_delimiter = ',')]
+    #[arg(value_name = "FELTS")]
+    #[arg(help = "The comma separated calldata of the call.")]
+    calldata: Vec<Felt>,
+}
+
+impl ExecArgs {
+    pub async fn run(&self) -> Result<()> {
+        let call = Call {
+            to: self.contract,
+            selector: get_selector_from_name(&self.entrypoint)?,
+            calldata: self.calldata.clone(),
+        };
+
+        let transaction_hash = session::execute(self.session.chain_id, vec![call]).await?;
+        println!("Transaction sent: {transaction_hash:#x}");
+
+        Ok(())
+    }
+}
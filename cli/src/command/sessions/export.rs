@@ -0,0 +1,37 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+use slot::session;
+
+use super::SessionArgs;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Export session options")]
+pub struct ExportArgs {
+    #[command(flatten)]
+    session: SessionArgs,
+
+    #[arg(long, short)]
+    #[arg(value_name = "PATH")]
+    #[arg(help = "The file to write the session to. Printed to stdout by default.")]
+    file: Option<PathBuf>,
+}
+
+impl ExportArgs {
+    pub async fn run(&self) -> Result<()> {
+        let session = self.session.load()?;
+        let exported = session::export(&session)?;
+
+        match &self.file {
+            Some(path) => {
+                fs::write(path, exported)?;
+                eprintln!("Session exported to {}.", path.display());
+            }
+            None => println!("{exported}"),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{ensure, Result};
+use clap::Args;
+use slot::credential::Credentials;
+use slot::session;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Import session options")]
+pub struct ImportArgs {
+    #[arg(value_name = "PATH")]
+    #[arg(help = "The file of the session exported with `slot sessions export`.")]
+    path: PathBuf,
+}
+
+impl ImportArgs {
+    pub async fn run(&self) -> Result<()> {
+        let credentials = Credentials::load()?;
+
+        let contents = fs::read_to_string(&self.path)?;
+        let session = session::import(&contents)?;
+
+        ensure!(
+            session.auth.username == credentials.account.username,
+            "The session belongs to '{}', but you are logged in as '{}'.",
+            session.auth.username,
+            credentials.account.username
+        );
+
+        let path = session::store(session.chain_id, &session)?;
+        println!(
+            "Imported the session for chain {} to {}.",
+            session.chain_name(),
+            path.display()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use clap::Args;
+use comfy_table::Cell;
+use serde::Serialize;
+use slot::api::Client;
+use slot::credential::Credentials;
+use slot::session;
+
+use super::{fetch_sessions, find_remote, format_timestamp, SessionState};
+use crate::output::{self, Render};
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "List sessions options")]
+pub struct ListArgs;
+
+impl ListArgs {
+    pub async fn run(&self) -> Result<()> {
+        let credentials = Credentials::load()?;
+        let local = session::list()?;
+
+        let client = Client::new_with_token(credentials.access_token.clone());
+        let remote_sessions = fetch_sessions(&client, &credentials).await?;
+
+        let mut sessions = Vec::new();
+        let mut matched = Vec::new();
+
+        for session in &local {
+            let remote = find_remote(session, &remote_sessions);
+            let expires_at = session.expires_at;
+
+            let state = match remote {
+                Some(remote) => SessionState::new(expires_at, remote.is_revoked).to_string(),
+                None => format!(
+                    "{} (unknown to server)",
+                    SessionState::new(expires_at, false)
+                ),
+            };
+
+            if let Some(remote) = remote {
+                matched.push(remote.id.clone());
+            }
+
+            sessions.push(SessionEntry {
+                chain: session.chain_name(),
+                app: remote.map(|r| r.app_id.clone()),
+                expires_at,
+                state,
+                stored_locally: true,
+                policies: Some(session.policies.len()),
+            });
+        }
+
+        for session in remote_sessions.iter().filter(|r| !matched.contains(&r.id)) {
+            sessions.push(SessionEntry {
+                chain: session.chain_id.clone(),
+                app: Some(session.app_id.clone()),
+                expires_at: session.expires_at,
+                state: SessionState::new(session.expires_at, session.is_revoked).to_string(),
+                stored_locally: false,
+                policies: None,
+            });
+        }
+
+        output::print(&sessions)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SessionEntry {
+    chain: String,
+    app: Option<String>,
+    /// The expiration timestamp, in seconds.
+    expires_at: u64,
+    state: String,
+    stored_locally: bool,
+    /// The number of policies, only known for the sessions stored locally.
+    policies: Option<usize>,
+}
+
+impl Render for Vec<SessionEntry> {
+    fn print_table(&self) {
+        if self.is_empty() {
+            println!("No sessions found.");
+            return;
+        }
+
+        let mut table = output::table(vec![
+            Cell::new("Chain"),
+            Cell::new("App"),
+            Cell::new("Expires At"),
+            Cell::new("State"),
+            Cell::new("Stored Locally"),
+            Cell::new("Policies"),
+        ]);
+
+        for session in self {
+            table.add_row(vec![
+                Cell::new(&session.chain),
+                Cell::new(session.app.as_deref().unwrap_or("-")),
+                Cell::new(format_timestamp(session.expires_at)),
+                Cell::new(&session.state),
+                Cell::new(if session.stored_locally { "✓" } else { "✗" }),
+                Cell::new(session.policies.map_or("-".to_string(), |p| p.to_string())),
+            ]);
+        }
+
+        println!("{table}");
+    }
+}
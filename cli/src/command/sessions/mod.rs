@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Args, Subcommand};
+use slot::api::Client;
+use slot::credential::Credentials;
+use slot::graphql::session::sessions::{ResponseData, SessionsSessionsEdgesNode, Variables};
+use slot::graphql::session::Sessions;
+use slot::graphql::GraphQLQuery;
+use slot::session::FullSessionInfo;
+use starknet::core::types::Felt;
+use starknet::core::utils::cairo_short_string_to_felt;
+
+use self::check::CheckArgs;
+use self::exec::ExecArgs;
+use self::export::ExportArgs;
+use self::import::ImportArgs;
+use self::list::ListArgs;
+use self::renew::RenewArgs;
+use self::revoke::RevokeArgs;
+use self::show::ShowArgs;
+
+mod check;
+mod exec;
+mod export;
+mod import;
+mod list;
+mod renew;
+mod revoke;
+mod show;
+
+/// Command group for managing the sessions of the authenticated user
+#[derive(Subcommand, Debug)]
+pub enum SessionsCmd {
+    #[command(about = "List the sessions of the authenticated user.", aliases = ["ls"])]
+    List(ListArgs),
+
+    #[command(about = "Show the details of a session.")]
+    Show(ShowArgs),
+
+    #[command(about = "Revoke sessions, server-side and locally.")]
+    Revoke(RevokeArgs),
+
+    #[command(about = "Create a new session with the same policies as an existing one.")]
+    Renew(RenewArgs),
+
+    #[command(about = "Check offline whether calls are allowed by a session's policies.")]
+    Check(CheckArgs),
+
+    #[command(about = "Execute a call with a session, if its policies allow it.")]
+    Exec(ExecArgs),
+
+    #[command(
+        about = "Export a session, encrypted with a passphrase, to use it on another machine."
+    )]
+    Export(ExportArgs),
+
+    #[command(about = "Import a session exported with `slot sessions export`.")]
+    Import(ImportArgs),
+}
+
+impl SessionsCmd {
+    pub async fn run(&self) -> Result<()> {
+        match &self {
+            SessionsCmd::List(args) => args.run().await,
+            SessionsCmd::Show(args) => args.run().await,
+            SessionsCmd::Revoke(args) => args.run().await,
+            SessionsCmd::Renew(args) => args.run().await,
+            SessionsCmd::Check(args) => args.run().await,
+            SessionsCmd::Exec(args) => args.run().await,
+            SessionsCmd::Export(args) => args.run().await,
+            SessionsCmd::Import(args) => args.run().await,
+        }
+    }
+
+    pub fn renders_output(&self) -> bool {
+        matches!(self, SessionsCmd::List(_) | SessionsCmd::Show(_))
+    }
+}
+
+/// Arguments identifying a locally stored session.
+#[derive(Debug, Args)]
+pub struct SessionArgs {
+    #[arg(value_name = "CHAIN_ID")]
+    #[arg(value_parser = parse_chain_id)]
+    #[arg(help = "The chain id of the session, e.g. SN_SEPOLIA or its hex value.")]
+    chain_id: Felt,
+}
+
+impl SessionArgs {
+    /// Returns the stored session of the chain id, even if it has expired.
+    fn load(&self) -> Result<FullSessionInfo> {
+        slot::session::list()?
+            .into_iter()
+            .find(|session| session.chain_id == self.chain_id)
+            .with_context(|| format!("No session found for chain id {:#x}.", self.chain_id))
+    }
+}
+
+fn parse_chain_id(value: &str) -> Result<Felt> {
+    if value.starts_with("0x") {
+        Ok(Felt::from_str(value)?)
+    } else {
+        Ok(cairo_short_string_to_felt(value)?)
+    }
+}
+
+/// The state of a session, as known by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionState {
+    Active,
+    Expired,
+    Revoked,
+}
+
+impl SessionState {
+    fn new(expires_at: u64, is_revoked: bool) -> Self {
+        if is_revoked {
+            Self::Revoked
+        } else if expires_at <= Utc::now().timestamp() as u64 {
+            Self::Expired
+        } else {
+            Self::Active
+        }
+    }
+}
+
+impl std::fmt::Display for SessionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Active => write!(f, "active"),
+            Self::Expired => write!(f, "expired"),
+            Self::Revoked => write!(f, "revoked"),
+        }
+    }
+}
+
+/// A session as returned by the API.
+type RemoteSession = SessionsSessionsEdgesNode;
+
+/// Fetches all the sessions of the authenticated user from the API.
+async fn fetch_sessions(client: &Client, credentials: &Credentials) -> Result<Vec<RemoteSession>> {
+    let mut sessions = Vec::new();
+    let mut after = None;
+
+    loop {
+        let request_body = Sessions::build_query(Variables {
+            account_id: credentials.account.id.clone(),
+            first: Some(100),
+            after: after.take(),
+        });
+
+        let data: ResponseData = client.query(&request_body).await?;
+        let Some(connection) = data.sessions else {
+            break;
+        };
+
+        let nodes = connection
+            .edges
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.node);
+        sessions.extend(nodes);
+
+        if !connection.page_info.has_next_page {
+            break;
+        }
+        after = connection.page_info.end_cursor;
+    }
+
+    Ok(sessions)
+}
+
+/// Returns the session from the API matching the locally stored `session`, if any.
+///
+/// Sessions are matched by their authorization. Those stored without it, e.g. before it was
+/// recorded, are matched by chain and expiry.
+fn find_remote<'a>(
+    session: &FullSessionInfo,
+    remote: &'a [RemoteSession],
+) -> Option<&'a RemoteSession> {
+    if !session.authorization.is_empty() {
+        return remote.iter().find(|r| {
+            r.authorization.len() == session.authorization.len()
+                && r.authorization
+                    .iter()
+                    .zip(&session.authorization)
+                    .all(|(remote, local)| {
+                        Felt::from_str(remote).is_ok_and(|remote| remote == *local)
+                    })
+        });
+    }
+
+    let chain = session.chain_name();
+    remote
+        .iter()
+        .find(|r| r.chain_id == chain && r.expires_at == session.expires_at)
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp as i64, 0)
+        .map(|date| date.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use clap::Args;
+use slot::session;
+use url::Url;
+
+use super::{format_timestamp, SessionArgs};
+use crate::command::auth::callback;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Renew session options")]
+pub struct RenewArgs {
+    #[command(flatten)]
+    session: SessionArgs,
+
+    #[arg(long)]
+    #[arg(value_name = "URL")]
+    #[arg(
+        help = "The RPC URL of the network, if different from the one the session was created with."
+    )]
+    rpc_url: Option<Url>,
+}
+
+impl RenewArgs {
+    pub async fn run(&self) -> Result<()> {
+        let current = self.session.load()?;
+
+        let renew = session::renew(&current, self.rpc_url.clone());
+        let renewed = callback::until_cancelled(renew).await?;
+        let path = session::store(renewed.chain_id, &renewed)?;
+
+        println!(
+            "Session renewed, expires at {}.",
+            format_timestamp(renewed.expires_at)
+        );
+        println!("Stored at {}.", path.display());
+
+        Ok(())
+    }
+}
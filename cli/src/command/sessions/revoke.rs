@@ -0,0 +1,45 @@
+use anyhow::{ensure, Result};
+use clap::Args;
+use slot::api::Client;
+use slot::credential::Credentials;
+use slot::session;
+use starknet::core::types::Felt;
+
+use super::parse_chain_id;
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Revoke sessions options")]
+pub struct RevokeArgs {
+    #[arg(value_name = "CHAIN_ID")]
+    #[arg(value_parser = parse_chain_id)]
+    #[arg(help = "The chain id of the session to revoke, e.g. SN_SEPOLIA or its hex value.")]
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    chain_id: Option<Felt>,
+
+    #[arg(long)]
+    #[arg(help = "Revoke all the locally stored sessions.")]
+    all: bool,
+}
+
+impl RevokeArgs {
+    pub async fn run(&self) -> Result<()> {
+        let credentials = Credentials::load()?;
+
+        let sessions: Vec<_> = session::list()?
+            .into_iter()
+            .filter(|s| self.all || Some(s.chain_id) == self.chain_id)
+            .collect();
+
+        ensure!(!sessions.is_empty(), "No sessions found.");
+
+        let client = Client::new_with_token(credentials.access_token);
+        session::revoke(&client, &sessions).await?;
+
+        for revoked in &sessions {
+            session::delete(revoked.chain_id)?;
+            println!("Revoked the session for chain {}.", revoked.chain_name());
+        }
+
+        Ok(())
+    }
+}
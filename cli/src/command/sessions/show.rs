@@ -0,0 +1,103 @@
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+use slot::api::Client;
+use slot::credential::Credentials;
+use slot::session::SessionPolicy;
+use starknet::core::types::Felt;
+
+use super::{fetch_sessions, find_remote, format_timestamp, SessionArgs, SessionState};
+use crate::output::{self, Render};
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Show session options")]
+pub struct ShowArgs {
+    #[command(flatten)]
+    session: SessionArgs,
+}
+
+impl ShowArgs {
+    pub async fn run(&self) -> Result<()> {
+        let credentials = Credentials::load()?;
+        let session = self.session.load()?;
+
+        let client = Client::new_with_token(credentials.access_token.clone());
+        let remote = fetch_sessions(&client, &credentials).await?;
+        let remote = find_remote(&session, &remote);
+
+        let state = match remote {
+            Some(remote) => SessionState::new(remote.expires_at, remote.is_revoked),
+            None => SessionState::new(session.expires_at, false),
+        };
+
+        output::print(&SessionDetails {
+            chain: session.chain_name(),
+            username: session.auth.username.clone(),
+            address: session.auth.address,
+            expires_at: session.expires_at,
+            state: state.to_string(),
+            known_to_server: remote.is_some(),
+            app: remote.map(|remote| remote.app_id.clone()),
+            created_at: remote.map(|remote| remote.created_at.clone()),
+            created_from: remote
+                .and_then(|remote| remote.metadata.as_ref())
+                .map(|metadata| format!("{} on {}", metadata.browser, metadata.os)),
+            policies: session.policies.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SessionDetails {
+    chain: String,
+    username: String,
+    address: Felt,
+    /// The expiration timestamp, in seconds.
+    expires_at: u64,
+    state: String,
+    known_to_server: bool,
+    app: Option<String>,
+    created_at: Option<String>,
+    created_from: Option<String>,
+    policies: Vec<SessionPolicy>,
+}
+
+impl Render for SessionDetails {
+    fn print_table(&self) {
+        println!("Chain: {}", self.chain);
+        println!("Username: {}", self.username);
+        println!("Address: {:#x}", self.address);
+        println!("Expires At: {}", format_timestamp(self.expires_at));
+        println!("State: {}", self.state);
+
+        if self.known_to_server {
+            if let Some(app) = &self.app {
+                println!("App: {app}");
+            }
+            if let Some(created_at) = &self.created_at {
+                println!("Created At: {created_at}");
+            }
+            if let Some(created_from) = &self.created_from {
+                println!("Created From: {created_from}");
+            }
+        } else {
+            println!("The session doesn't match any of the sessions known by the server.");
+        }
+
+        println!();
+        println!("Policies:");
+        if self.policies.is_empty() {
+            println!("  Not recorded for this session.");
+        }
+
+        for policy in &self.policies {
+            match policy {
+                SessionPolicy::Call(call) => println!("  Call {:#x} {}", call.target, call.method),
+                SessionPolicy::TypedData(typed_data) => println!(
+                    "  Sign typed data {:#x} {:#x}",
+                    typed_data.domain_hash, typed_data.type_hash
+                ),
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use anyhow::Result;
 use chrono::prelude::*;
 use clap::Args;
-use comfy_table::{presets::UTF8_FULL, Cell, ContentArrangement, Table};
+use comfy_table::Cell;
+use serde::Serialize;
 use slot::api::Client;
 use slot::credential::Credentials;
 use slot::graphql::team::team_invoices::{InvoiceOrder, Variables};
@@ -9,6 +10,8 @@ use slot::graphql::team::team_invoices::{InvoiceOrderField, OrderDirection};
 use slot::graphql::team::TeamInvoices;
 use slot::graphql::GraphQLQuery;
 
+use crate::output::{self, Render};
+
 #[derive(Debug, Args)]
 pub struct InvoicesArgs {}
 
@@ -33,70 +36,30 @@ impl InvoicesArgs {
             .team
             .ok_or_else(|| anyhow::anyhow!("Team '{}' not found", team_name))?;
 
-        let edges = team.invoices.edges.unwrap_or_default();
-
-        if edges.is_empty() {
-            println!("No invoices found for team '{}'", team_name);
-            return Ok(());
-        }
-
-        let current_month = Utc::now().format("%Y-%m").to_string();
+        let invoices = team
+            .invoices
+            .edges
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.node)
+            .map(|node| Invoice {
+                month: node.month,
+                total_credits: node.total_credits,
+                incubator_credits: node.incubator_credits,
+                total_debits: node.total_debits,
+                slot_debits: node.slot_debits,
+                paymaster_debits: node.paymaster_debits,
+                net_amount: node.net_amount,
+                incubator_stage: node.incubator_stage,
+                finalized: node.finalized,
+            })
+            .collect();
 
-        for edge in edges.into_iter().flatten() {
-            if let Some(node) = edge.node {
-                let is_current_month = node.month == current_month;
-
-                let mut table = Table::new();
-                table
-                    .load_preset(UTF8_FULL)
-                    .set_content_arrangement(ContentArrangement::Dynamic);
-
-                let title = if is_current_month {
-                    format!("Invoices for team `{}` (Current Month)", team_name)
-                } else {
-                    format!("Invoices for team `{}`", team_name)
-                };
-
-                table.set_header(vec![title, "".to_string()]);
-
-                table.add_row(vec![Cell::new("Month"), Cell::new(&node.month)]);
-                table.add_row(vec![
-                    Cell::new("Total Credits (top up + reimbursement)"),
-                    Cell::new(format_credits(node.total_credits)),
-                ]);
-                table.add_row(vec![
-                    Cell::new(" -> Incubator Credits"),
-                    Cell::new(format_credits(node.incubator_credits)),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Debits (slot + paymaster)"),
-                    Cell::new(format_credits(node.total_debits)),
-                ]);
-                table.add_row(vec![
-                    Cell::new(" -> Slot Debits"),
-                    Cell::new(format_credits(node.slot_debits)),
-                ]);
-                table.add_row(vec![
-                    Cell::new(" -> Paymaster Debits"),
-                    Cell::new(format_credits(node.paymaster_debits)),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Net Amount Paid"),
-                    Cell::new(format_credits(node.net_amount)),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Incubator Stage"),
-                    Cell::new(node.incubator_stage.unwrap_or_else(|| "None".to_string())),
-                ]);
-                table.add_row(vec![
-                    Cell::new("Finalized"),
-                    Cell::new(if node.finalized { "Yes" } else { "No" }),
-                ]);
-
-                println!("{table}");
-                println!();
-            }
-        }
+        output::print(&Invoices {
+            team: team_name,
+            invoices,
+        })?;
 
         Ok(())
     }
@@ -106,3 +69,83 @@ fn format_credits(credits: i64) -> String {
     let dollars = credits as f64 / 100.0 / 1e6;
     format!("${:.2}", dollars)
 }
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct Invoices {
+    #[serde(skip)]
+    team: String,
+    invoices: Vec<Invoice>,
+}
+
+/// An invoice, with the amounts in credits.
+#[derive(Debug, Serialize)]
+struct Invoice {
+    month: String,
+    total_credits: i64,
+    incubator_credits: i64,
+    total_debits: i64,
+    slot_debits: i64,
+    paymaster_debits: i64,
+    net_amount: i64,
+    incubator_stage: Option<String>,
+    finalized: bool,
+}
+
+impl Render for Invoices {
+    fn print_table(&self) {
+        if self.invoices.is_empty() {
+            println!("No invoices found for team '{}'", self.team);
+            return;
+        }
+
+        let current_month = Utc::now().format("%Y-%m").to_string();
+
+        for invoice in &self.invoices {
+            let title = if invoice.month == current_month {
+                format!("Invoices for team `{}` (Current Month)", self.team)
+            } else {
+                format!("Invoices for team `{}`", self.team)
+            };
+
+            let mut table = output::table(vec![title, "".to_string()]);
+
+            table.add_row(vec![Cell::new("Month"), Cell::new(&invoice.month)]);
+            table.add_row(vec![
+                Cell::new("Total Credits (top up + reimbursement)"),
+                Cell::new(format_credits(invoice.total_credits)),
+            ]);
+            table.add_row(vec![
+                Cell::new(" -> Incubator Credits"),
+                Cell::new(format_credits(invoice.incubator_credits)),
+            ]);
+            table.add_row(vec![
+                Cell::new("Debits (slot + paymaster)"),
+                Cell::new(format_credits(invoice.total_debits)),
+            ]);
+            table.add_row(vec![
+                Cell::new(" -> Slot Debits"),
+                Cell::new(format_credits(invoice.slot_debits)),
+            ]);
+            table.add_row(vec![
+                Cell::new(" -> Paymaster Debits"),
+                Cell::new(format_credits(invoice.paymaster_debits)),
+            ]);
+            table.add_row(vec![
+                Cell::new("Net Amount Paid"),
+                Cell::new(format_credits(invoice.net_amount)),
+            ]);
+            table.add_row(vec![
+                Cell::new("Incubator Stage"),
+                Cell::new(invoice.incubator_stage.as_deref().unwrap_or("None")),
+            ]);
+            table.add_row(vec![
+                Cell::new("Finalized"),
+                Cell::new(if invoice.finalized { "Yes" } else { "No" }),
+            ]);
+
+            println!("{table}");
+            println!();
+        }
+    }
+}
@@ -1,5 +1,6 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Args;
+use serde::Serialize;
 use slot::api::Client;
 use slot::credential::Credentials;
 use slot::graphql::team::{
@@ -8,6 +9,8 @@ use slot::graphql::team::{
 };
 use slot::graphql::GraphQLQuery;
 
+use crate::output::{self, Render};
+
 #[derive(Debug, Args, serde::Serialize)]
 #[command(next_help_heading = "Team list options")]
 pub struct TeamListArgs;
@@ -22,25 +25,35 @@ impl TeamListArgs {
 
         let data: team_members_list::ResponseData = client.query(&request_body).await?;
 
-        if let Some(team_list) = data.team {
-            if team_list.deleted {
-                println!("Team '{}' not found or has been deleted", team);
-                return Ok(());
-            }
-
-            team_list
-                .members
-                .edges
-                .into_iter()
-                .flatten()
-                .for_each(|edge| {
-                    if let Some(node) = edge.and_then(|edge| edge.node) {
-                        println!("  {}", node.id)
-                    }
-                });
-        }
+        let Some(team_list) = data.team.filter(|team| !team.deleted) else {
+            bail!("Team '{}' not found or has been deleted", team);
+        };
 
-        Ok(())
+        let members = team_list
+            .members
+            .edges
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| edge.and_then(|edge| edge.node))
+            .map(|node| node.id)
+            .collect();
+
+        output::print(&TeamMembers { members })
+    }
+}
+
+/// The accounts of the members of a team.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct TeamMembers {
+    members: Vec<String>,
+}
+
+impl Render for TeamMembers {
+    fn print_table(&self) {
+        for member in &self.members {
+            println!("  {}", member);
+        }
     }
 }
 
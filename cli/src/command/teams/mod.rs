@@ -57,4 +57,11 @@ impl Teams {
             TeamsCommands::Invoices(args) => args.run(self.name.clone()).await,
         }
     }
+
+    pub fn renders_output(&self) -> bool {
+        matches!(
+            self.teams_commands,
+            TeamsCommands::List(_) | TeamsCommands::Invoices(_)
+        )
+    }
 }
@@ -1,16 +1,25 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 use num_bigint as _;
-use update_informer as _;
 
 mod command;
+mod output;
 
 use crate::command::Command;
+use crate::output::OutputFormat;
 use clap::Parser;
 
 /// Slot CLI for Cartridge
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
+    #[arg(long, global = true, env = "SLOT_PROFILE")]
+    #[arg(help = "The profile to use for the credentials and sessions.")]
+    pub profile: Option<String>,
+
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Table)]
+    #[arg(help = "The format of the command results.")]
+    pub output: OutputFormat,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -18,7 +27,15 @@ pub struct Cli {
 #[tokio::main]
 async fn main() {
     env_logger::init();
-    let cli = Cli::parse();
+    let cli = Cli::parse_from(output::rewrite_legacy_args(std::env::args()));
+    output::select(cli.output);
+
+    if let Some(profile) = &cli.profile {
+        if let Err(e) = slot::profile::select(profile) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
 
     match &cli.command.run().await {
         Ok(_) => {}
@@ -0,0 +1,185 @@
+//! Rendering of the command results, either for humans or as JSON or YAML for scripts.
+//!
+//! The commands build a result implementing [`Render`] and pass it to [`print`], which renders it
+//! according to the format selected with the global `--output` flag.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use clap::ValueEnum;
+use comfy_table::{presets::UTF8_FULL, ContentArrangement, Row, Table};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable tables and text.
+    #[default]
+    Table,
+    Json,
+    Yaml,
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.to_possible_value().expect("no skipped values");
+        f.write_str(value.get_name())
+    }
+}
+
+/// The commands whose `--output` flag took a file path before it became the global flag, with the
+/// flag now taking the path.
+const LEGACY_OUTPUT_FLAGS: [(&str, &str, &str); 1] =
+    [("merkle-drops", "snapshot", "--output-file")];
+
+/// Rewrites the `--output <PATH>` passed to the commands of [`LEGACY_OUTPUT_FLAGS`] to the flag
+/// now taking the path, so that the scripts written for them keep working. The values naming an
+/// output format are left to the global flag.
+pub fn rewrite_legacy_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut args = args.into_iter().collect::<Vec<_>>();
+
+    let legacy = args.windows(2).enumerate().find_map(|(i, pair)| {
+        LEGACY_OUTPUT_FLAGS
+            .iter()
+            .find(|(group, command, _)| pair[0] == *group && pair[1] == *command)
+            .map(|(_, _, flag)| (i + 2, *flag))
+    });
+    let Some((start, flag)) = legacy else {
+        return args;
+    };
+
+    let is_format = |value: &str| OutputFormat::from_str(value, true).is_ok();
+    for i in start..args.len() {
+        let rewritten = match args[i].strip_prefix("--output") {
+            Some("") if args.get(i + 1).is_some_and(|value| !is_format(value)) => flag.to_string(),
+            Some(value) => match value.strip_prefix('=') {
+                Some(value) if !is_format(value) => format!("{flag}={value}"),
+                _ => continue,
+            },
+            None => continue,
+        };
+
+        eprintln!("warning: `--output <PATH>` is deprecated here, use `{flag}` instead.");
+        args[i] = rewritten;
+    }
+
+    args
+}
+
+/// The format selected for the lifetime of the process.
+static SELECTED_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Select the output format for the lifetime of the process. Can only be called once.
+pub fn select(format: OutputFormat) {
+    let _ = SELECTED_FORMAT.set(format);
+}
+
+/// Returns the selected output format, defaulting to [`OutputFormat::Table`].
+pub fn format() -> OutputFormat {
+    SELECTED_FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Whether the results are rendered for humans, in which case commands can print additional
+/// messages, e.g. hints or warnings, without breaking the output.
+pub fn is_human() -> bool {
+    format() == OutputFormat::Table
+}
+
+/// The result of a command.
+pub trait Render: Serialize {
+    /// Prints the result for humans.
+    fn print_table(&self);
+}
+
+/// Prints the result of a command in the selected format.
+pub fn print<T: Render>(result: &T) -> Result<()> {
+    match format() {
+        OutputFormat::Table => result.print_table(),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(result)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(result)?),
+    }
+    Ok(())
+}
+
+/// Prints one of the results streamed by a command, e.g. when following logs. JSON results are
+/// printed one per line, and YAML ones as separate documents.
+pub fn print_streamed<T: Render>(result: &T) -> Result<()> {
+    match format() {
+        OutputFormat::Table => result.print_table(),
+        OutputFormat::Json => println!("{}", serde_json::to_string(result)?),
+        OutputFormat::Yaml => print!("---\n{}", serde_yaml::to_string(result)?),
+    }
+    Ok(())
+}
+
+/// Returns a table with the style shared by the commands, and the given header.
+pub fn table(header: impl Into<Row>) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(header);
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rewrite_legacy_args;
+
+    fn rewrite(args: &[&str]) -> Vec<String> {
+        rewrite_legacy_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn rewrites_legacy_output_path() {
+        let args = rewrite(&["slot", "merkle-drops", "snapshot", "--output", "drop.json"]);
+        assert_eq!(
+            args,
+            [
+                "slot",
+                "merkle-drops",
+                "snapshot",
+                "--output-file",
+                "drop.json"
+            ]
+        );
+
+        let args = rewrite(&["slot", "merkle-drops", "snapshot", "--output=drop.json"]);
+        assert_eq!(
+            args,
+            [
+                "slot",
+                "merkle-drops",
+                "snapshot",
+                "--output-file=drop.json"
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_output_format() {
+        let args = ["slot", "merkle-drops", "snapshot", "--output", "json"];
+        assert_eq!(rewrite(&args), args);
+
+        let args = ["slot", "merkle-drops", "snapshot", "--output=yaml"];
+        assert_eq!(rewrite(&args), args);
+    }
+
+    #[test]
+    fn keeps_new_flag() {
+        let args = [
+            "slot",
+            "merkle-drops",
+            "snapshot",
+            "--output-file",
+            "drop.json",
+        ];
+        assert_eq!(rewrite(&args), args);
+    }
+
+    #[test]
+    fn ignores_other_commands() {
+        let args = ["slot", "sessions", "export", "--output", "session.json"];
+        assert_eq!(rewrite(&args), args);
+    }
+}
@@ -1,27 +1,57 @@
 use std::fmt::{self};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use graphql_client::Response;
-use reqwest::RequestBuilder;
+use rand::Rng;
+use reqwest::header::{HeaderMap, HeaderValue, IntoHeaderName, RETRY_AFTER};
+use reqwest::{RequestBuilder, StatusCode};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::Mutex;
 use url::Url;
 
+use crate::credential::{self, AccessToken};
 use crate::error::Error;
-use crate::{credential::AccessToken, vars};
+use crate::vars;
+
+pub use reqwest::{header, Certificate, Proxy};
+
+/// The default timeout for a single request to the API.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// The default timeout for establishing a connection to the API.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// The `User-Agent` product token identifying this library.
+const USER_AGENT: &str = concat!("slot/", env!("CARGO_PKG_VERSION"));
 
 #[derive(Debug)]
 pub struct Client {
     base_url: Url,
     client: reqwest::Client,
-    access_token: Option<AccessToken>,
+    access_token: Mutex<Option<AccessToken>>,
+    /// Whether requests may be sent without an access token.
+    anonymous: bool,
+    retry_policy: RetryPolicy,
+    timeout: Duration,
 }
 
 impl Client {
+    /// Creates a client with the default configuration, targeting the API url set by the
+    /// `CARTRIDGE_API_URL` environment variable.
     pub fn new() -> Self {
-        Self {
-            access_token: None,
-            client: reqwest::Client::new(),
-            base_url: Url::parse(vars::get_cartridge_api_url().as_str()).expect("valid url"),
-        }
+        Self::builder().build().expect("failed to build api client")
+    }
+
+    /// Returns a [`ClientBuilder`] to configure a new client.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// Creates a client for the public queries of the API (e.g. `price`, `lookupPaymaster`),
+    /// which don't require the user to be authenticated.
+    pub fn new_anonymous() -> Self {
+        Self::builder()
+            .anonymous()
+            .build()
+            .expect("failed to build api client")
     }
 
     pub fn new_with_token(token: AccessToken) -> Self {
@@ -31,74 +61,300 @@ impl Client {
     }
 
     pub fn set_token(&mut self, token: AccessToken) {
-        self.access_token = Some(token);
+        *self.access_token.get_mut() = Some(token);
     }
 
+    /// Returns the current access token of the client, which may have been refreshed since the
+    /// client was created.
+    pub async fn token(&self) -> Option<AccessToken> {
+        self.access_token.lock().await.clone()
+    }
+
+    /// Send a GraphQL request to the API.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unauthorized`] without sending the request if the client has no access
+    /// token and wasn't created as an anonymous client.
+    ///
+    /// Queries that fail with a transient error (rate limiting, gateway errors, timeouts) are
+    /// retried according to the client's [`RetryPolicy`]. Mutations are only retried when the
+    /// connection to the API couldn't be established, as they may have been applied otherwise.
+    ///
+    /// Mutations waiting for the deployment they change (`wait: true`) aren't subject to the
+    /// request timeout, as they routinely take longer.
+    ///
+    /// If the access token has a refresh token, it is refreshed shortly before it expires, or
+    /// once if the API rejects it. The refreshed token replaces the one of the stored
+    /// credentials it was loaded from.
     pub async fn query<R, T>(&self, body: &T) -> Result<R, Error>
     where
         R: DeserializeOwned,
         T: Serialize + ?Sized,
     {
-        let path = "/query";
-        let token = self.access_token.as_ref().map(|t| t.token.as_str());
+        match self.query_with_retry(body).await {
+            Err(Error::InvalidOAuth) if self.refresh_access_token().await? => {
+                self.query_with_retry(body).await
+            }
+            result => result,
+        }
+    }
 
-        // TODO: return this as an error if token is None
-        let bearer = format!("Bearer {}", token.unwrap_or_default());
+    async fn query_with_retry<R, T>(&self, body: &T) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+        T: Serialize + ?Sized,
+    {
+        let operation = Operation::of(body);
+        let mut attempt = 0;
 
-        let response = self
-            .post(path)
-            .header("Authorization", bearer)
-            .json(body)
-            .send()
-            .await;
+        loop {
+            match self.send_query(body, &operation).await {
+                Err(err) if attempt < self.retry_policy.max_retries => {
+                    let delay = self.retry_policy.retry_delay(&err, attempt, &operation);
+                    let Some(delay) = delay else {
+                        return Err(err);
+                    };
 
-        if response.is_err() {
-            return Err(Error::ReqwestError(response.err().unwrap()));
+                    attempt += 1;
+                    tracing::debug!(%err, ?delay, attempt, "Retrying API request.");
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
         }
+    }
+
+    /// Send a single GraphQL request attempt to the API.
+    async fn send_query<R, T>(&self, body: &T, operation: &Operation) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+        T: Serialize + ?Sized,
+    {
+        let path = "/query";
+
+        let request = if operation.waits {
+            self.client.post(self.get_url(path))
+        } else {
+            self.post(path)
+        };
+
+        let request = match self.fresh_access_token().await {
+            Some(token) => request.bearer_auth(&token.token),
+            None if self.anonymous => request,
+            None => return Err(Error::Unauthorized),
+        };
+
+        let res = request.json(body).send().await.map_err(map_reqwest_error)?;
 
-        let res = response?;
+        let status = res.status();
 
-        if res.status() == 403 {
+        if status == StatusCode::FORBIDDEN || status == StatusCode::UNAUTHORIZED {
             return Err(Error::InvalidOAuth);
         }
 
-        if !res.status().is_success() {
-            return Err(anyhow::anyhow!("API error: {}", res.status()).into());
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = parse_retry_after(res.headers());
+            return Err(Error::RateLimited { retry_after });
+        }
+
+        if status.is_server_error() {
+            let retry_after = parse_retry_after(res.headers());
+            return Err(Error::ServerError {
+                status,
+                retry_after,
+            });
         }
 
-        let res: Response<R> = res.json().await?;
+        if !status.is_success() {
+            return Err(anyhow::anyhow!("API error: {}", status).into());
+        }
+
+        let res: Response<R> = res.json().await.map_err(map_reqwest_error)?;
 
         if let Some(errors) = res.errors {
             Err(Error::Api(GraphQLErrors(errors)))
         } else {
-            Ok(res.data.unwrap())
+            res.data.ok_or(Error::MissingData)
         }
     }
 
     pub async fn oauth2(&self, code: &str) -> Result<AccessToken, Error> {
-        #[derive(Deserialize)]
-        struct OauthToken {
-            #[serde(rename(deserialize = "access_token"))]
-            token: String,
-            #[serde(rename(deserialize = "token_type"))]
-            r#type: String,
+        let path = "/oauth2/token";
+        let form = [("code", code)];
+
+        // The authorization code can only be exchanged once, so this request is never retried.
+        let response = self
+            .post(path)
+            .form(&form)
+            .send()
+            .await
+            .map_err(map_reqwest_error)?;
+        let token: OauthToken = response.json().await.map_err(map_reqwest_error)?;
+
+        Ok(token.into())
+    }
+
+    /// Returns the access token, refreshed first if it is about to expire. If the refresh fails,
+    /// the current token is returned as it may still be accepted.
+    async fn fresh_access_token(&self) -> Option<AccessToken> {
+        let mut token = self.access_token.lock().await;
+
+        let expiring = token.as_ref().is_some_and(|token| {
+            token.refresh_token.is_some() && token.expires_within(TOKEN_REFRESH_MARGIN)
+        });
+
+        if expiring {
+            let current = token.as_ref().expect("qed; token exists");
+            match self.refresh(current).await {
+                Ok(refreshed) => *token = Some(refreshed),
+                Err(err) => tracing::warn!(%err, "Failed to refresh the access token."),
+            }
         }
 
+        token.clone()
+    }
+
+    /// Refreshes the access token. Returns `false` if the token can't be refreshed.
+    async fn refresh_access_token(&self) -> Result<bool, Error> {
+        let mut token = self.access_token.lock().await;
+
+        let Some(current) = token.as_ref().filter(|t| t.refresh_token.is_some()) else {
+            return Ok(false);
+        };
+
+        *token = Some(self.refresh(current).await?);
+        Ok(true)
+    }
+
+    /// Exchanges the refresh token of `token` for a new access token, and updates the stored
+    /// credentials authenticated by `token`.
+    async fn refresh(&self, token: &AccessToken) -> Result<AccessToken, Error> {
+        let refresh_token = token
+            .refresh_token
+            .as_deref()
+            .expect("qed; has refresh token");
+
         let path = "/oauth2/token";
-        let form = [("code", code)];
+        let form = [
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", OAUTH_CLIENT_ID),
+        ];
 
-        let response = self.post(path).form(&form).send().await?;
-        let token: OauthToken = response.json().await?;
+        let response = self
+            .post(path)
+            .form(&form)
+            .send()
+            .await
+            .map_err(map_reqwest_error)?;
 
-        Ok(AccessToken {
-            token: token.token,
-            r#type: token.r#type,
-        })
+        let status = response.status();
+        if status.is_client_error() {
+            return Err(Error::InvalidOAuth);
+        }
+
+        if !status.is_success() {
+            return Err(anyhow::anyhow!("API error: {}", status).into());
+        }
+
+        let refreshed: OauthToken = response.json().await.map_err(map_reqwest_error)?;
+        let mut refreshed = AccessToken::from(refreshed);
+
+        // The server may not rotate the refresh token.
+        if refreshed.refresh_token.is_none() {
+            refreshed.refresh_token = token.refresh_token.clone();
+        }
+
+        if let Err(err) = credential::update_stored_token(token, &refreshed) {
+            tracing::warn!(%err, "Failed to store the refreshed access token.");
+        }
+
+        tracing::debug!("Access token refreshed.");
+        Ok(refreshed)
+    }
+
+    /// Starts the device authorization flow ([RFC 8628]), which lets the user approve the login
+    /// from another device. The user must visit the verification uri and enter the user code,
+    /// while [`poll_device_token`](Self::poll_device_token) waits for the approval.
+    ///
+    /// [RFC 8628]: https://datatracker.ietf.org/doc/html/rfc8628
+    pub async fn device_authorization(&self) -> Result<DeviceAuthorization, Error> {
+        let path = "/oauth2/device/code";
+        let form = [("client_id", OAUTH_CLIENT_ID)];
+
+        let response = self
+            .post(path)
+            .form(&form)
+            .send()
+            .await
+            .map_err(map_reqwest_error)?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("API error: {}", response.status()).into());
+        }
+
+        response.json().await.map_err(map_reqwest_error)
+    }
+
+    /// Polls the token endpoint until the user approves (or denies) the device authorization,
+    /// or until it expires.
+    pub async fn poll_device_token(
+        &self,
+        authorization: &DeviceAuthorization,
+    ) -> Result<AccessToken, Error> {
+        let path = "/oauth2/token";
+        let form = [
+            ("grant_type", DEVICE_CODE_GRANT_TYPE),
+            ("device_code", authorization.device_code.as_str()),
+            ("client_id", OAUTH_CLIENT_ID),
+        ];
+
+        let deadline = Instant::now() + Duration::from_secs(authorization.expires_in);
+        let mut interval = Duration::from_secs(authorization.interval);
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            if Instant::now() >= deadline {
+                return Err(Error::LoginExpired);
+            }
+
+            let response = match self.post(path).form(&form).send().await {
+                Ok(response) => response,
+                // Keep polling through transient network failures.
+                Err(err) if err.is_timeout() || err.is_connect() => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            let status = response.status();
+
+            if status.is_success() {
+                let token: OauthToken = response.json().await.map_err(map_reqwest_error)?;
+                return Ok(token.into());
+            }
+
+            if status.is_server_error() {
+                continue;
+            }
+
+            let error: OauthError = response.json().await.map_err(map_reqwest_error)?;
+            match error.error.as_str() {
+                "authorization_pending" => {}
+                "slow_down" => interval += SLOW_DOWN_INCREMENT,
+                "access_denied" => return Err(Error::LoginDenied),
+                "expired_token" => return Err(Error::LoginExpired),
+                other => {
+                    let description = error.error_description.unwrap_or_default();
+                    return Err(anyhow::anyhow!("OAuth error: {other} {description}").into());
+                }
+            }
+        }
     }
 
     fn post(&self, path: &str) -> RequestBuilder {
         let url = self.get_url(path);
-        self.client.post(url)
+        self.client.post(url).timeout(self.timeout)
     }
 
     fn get_url(&self, path: &str) -> Url {
@@ -114,6 +370,404 @@ impl Default for Client {
     }
 }
 
+/// The OAuth client identifier of the Slot CLI.
+const OAUTH_CLIENT_ID: &str = "slot";
+/// The grant type used to poll the token of a device authorization.
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+/// How much to increase the polling interval when the server asks to slow down.
+const SLOW_DOWN_INCREMENT: Duration = Duration::from_secs(5);
+
+/// How long before its expiration an access token is refreshed.
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+struct OauthToken {
+    #[serde(rename(deserialize = "access_token"))]
+    token: String,
+    #[serde(rename(deserialize = "token_type"))]
+    r#type: String,
+    /// The lifetime of the access token, in seconds.
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+}
+
+impl From<OauthToken> for AccessToken {
+    fn from(value: OauthToken) -> Self {
+        let expires_at = value.expires_in.map(|expires_in| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            now.as_secs() + expires_in
+        });
+
+        Self {
+            token: value.token,
+            r#type: value.r#type,
+            expires_at,
+            refresh_token: value.refresh_token,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OauthError {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// A pending device authorization.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DeviceAuthorization {
+    /// The code used to poll the token, must not be shown to the user.
+    pub device_code: String,
+    /// The code the user must enter on the verification page.
+    pub user_code: String,
+    /// The page where the user approves the login.
+    pub verification_uri: String,
+    /// The verification page with the user code already filled in.
+    pub verification_uri_complete: Option<String>,
+    /// The lifetime of the device code, in seconds.
+    pub expires_in: u64,
+    /// The minimum interval between two polls, in seconds.
+    #[serde(default = "default_polling_interval")]
+    pub interval: u64,
+}
+
+fn default_polling_interval() -> u64 {
+    5
+}
+
+/// A builder to configure a [`Client`].
+///
+/// ```no_run
+/// # fn main() -> Result<(), slot::Error> {
+/// use slot::api::{header::HeaderValue, Client, Proxy};
+///
+/// let client = Client::builder()
+///     .user_agent("sozo/1.0.0")
+///     .proxy(Proxy::https("http://proxy.internal:3128")?)
+///     .header("x-team", HeaderValue::from_static("dojo"))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ClientBuilder {
+    base_url: Option<Url>,
+    access_token: Option<AccessToken>,
+    anonymous: bool,
+    retry_policy: RetryPolicy,
+    timeout: Duration,
+    connect_timeout: Duration,
+    user_agent: Option<String>,
+    headers: HeaderMap,
+    proxies: Vec<Proxy>,
+    root_certificates: Vec<Certificate>,
+    built_in_root_certificates: bool,
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self {
+            base_url: None,
+            access_token: None,
+            anonymous: false,
+            retry_policy: RetryPolicy::default(),
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            user_agent: None,
+            headers: HeaderMap::new(),
+            proxies: Vec::new(),
+            root_certificates: Vec::new(),
+            built_in_root_certificates: true,
+        }
+    }
+
+    /// Set the base url of the API. Defaults to the url set by the `CARTRIDGE_API_URL`
+    /// environment variable, or the production API if unset.
+    pub fn base_url(mut self, url: Url) -> Self {
+        self.base_url = Some(url);
+        self
+    }
+
+    /// Set the access token used to authenticate the requests.
+    pub fn access_token(mut self, token: AccessToken) -> Self {
+        self.access_token = Some(token);
+        self
+    }
+
+    /// Allow sending requests without an access token, for the public queries of the API.
+    pub fn anonymous(mut self) -> Self {
+        self.anonymous = true;
+        self
+    }
+
+    /// Set the policy used to retry requests that failed with a transient error.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Set the timeout of a single request attempt. Defaults to 30 seconds. Mutations waiting
+    /// for the deployment they change aren't subject to it.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the timeout for establishing a connection. Defaults to 10 seconds.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Identify the tool using this client. The product token (e.g. `sozo/1.0.0`) is sent in
+    /// the `User-Agent` header, followed by the slot version.
+    pub fn user_agent(mut self, product: impl Into<String>) -> Self {
+        self.user_agent = Some(product.into());
+        self
+    }
+
+    /// Add a header sent with every request.
+    pub fn header<K: IntoHeaderName>(mut self, name: K, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Add headers sent with every request.
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Route the requests through a proxy.
+    ///
+    /// The system proxies set through the `HTTP_PROXY`/`HTTPS_PROXY` environment variables are
+    /// used if no proxy is configured.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Trust an additional root certificate, e.g. the CA of a TLS-intercepting corporate proxy.
+    pub fn add_root_certificate(mut self, cert: Certificate) -> Self {
+        self.root_certificates.push(cert);
+        self
+    }
+
+    /// Trust all the root certificates of a PEM encoded bundle.
+    pub fn add_root_certificates_pem(mut self, pem_bundle: &[u8]) -> Result<Self, Error> {
+        self.root_certificates
+            .extend(Certificate::from_pem_bundle(pem_bundle)?);
+        Ok(self)
+    }
+
+    /// Whether to trust the built-in root certificates. Disable this to only trust the
+    /// certificates added with [`add_root_certificate`](Self::add_root_certificate). Defaults
+    /// to `true`.
+    pub fn built_in_root_certificates(mut self, enabled: bool) -> Self {
+        self.built_in_root_certificates = enabled;
+        self
+    }
+
+    pub fn build(self) -> Result<Client, Error> {
+        let base_url = match self.base_url {
+            Some(url) => url,
+            None => Url::parse(&vars::get_cartridge_api_url())
+                .map_err(|e| anyhow::anyhow!("invalid API url: {e}"))?,
+        };
+
+        let user_agent = match self.user_agent {
+            Some(product) => format!("{product} {USER_AGENT}"),
+            None => USER_AGENT.to_string(),
+        };
+
+        let mut builder = reqwest::Client::builder()
+            .user_agent(user_agent)
+            .default_headers(self.headers)
+            .connect_timeout(self.connect_timeout)
+            .tls_built_in_root_certs(self.built_in_root_certificates);
+
+        for proxy in self.proxies {
+            builder = builder.proxy(proxy);
+        }
+
+        for cert in self.root_certificates {
+            builder = builder.add_root_certificate(cert);
+        }
+
+        Ok(Client {
+            base_url,
+            client: builder.build()?,
+            access_token: Mutex::new(self.access_token),
+            anonymous: self.anonymous,
+            retry_policy: self.retry_policy,
+            timeout: self.timeout,
+        })
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The kind of GraphQL operation sent by a request, which decides how it is retried.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Operation {
+    /// Whether the operation is a mutation, which must not be replayed if the server may have
+    /// applied it.
+    is_mutation: bool,
+    /// Whether the mutation waits for the deployment it changes (`wait: true`).
+    waits: bool,
+}
+
+impl Operation {
+    /// Classifies the GraphQL request `body`, e.g. a [`graphql_client::QueryBody`]. Bodies that
+    /// aren't GraphQL requests are treated as queries.
+    fn of<T: Serialize + ?Sized>(body: &T) -> Self {
+        let Ok(body) = serde_json::to_value(body) else {
+            return Self::default();
+        };
+
+        let is_mutation = body
+            .get("query")
+            .and_then(|query| query.as_str())
+            .is_some_and(is_mutation_document);
+
+        let waits = is_mutation
+            && body
+                .pointer("/variables/wait")
+                .and_then(|wait| wait.as_bool())
+                .unwrap_or(false);
+
+        Self { is_mutation, waits }
+    }
+}
+
+/// Whether the first operation of a GraphQL document is a mutation.
+fn is_mutation_document(document: &str) -> bool {
+    document
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .flat_map(str::split_whitespace)
+        .next()
+        .is_some_and(|keyword| keyword == "mutation")
+}
+
+/// Policy for retrying API requests that failed with a transient error.
+///
+/// The delay between attempts grows exponentially from `initial_backoff` up to `max_backoff`,
+/// with random jitter applied. If the server answers with a `Retry-After` header, its value is
+/// used as the delay instead, unless it exceeds `max_backoff`, in which case the request is not
+/// retried at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of retries after the initial attempt.
+    pub max_retries: u32,
+    /// The delay before the first retry.
+    pub initial_backoff: Duration,
+    /// The upper bound of the delay between two attempts.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub const fn none() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    /// Returns how long to wait before retrying a request that failed with `err` on the given
+    /// (zero-based) `attempt`, or `None` if the request should not be retried.
+    ///
+    /// Mutations are only retried if the request couldn't be sent, as the server may have
+    /// applied them before failing otherwise.
+    fn retry_delay(&self, err: &Error, attempt: u32, operation: &Operation) -> Option<Duration> {
+        if operation.is_mutation {
+            return match err {
+                Error::ReqwestError(err) if err.is_connect() => Some(self.backoff(attempt)),
+                _ => None,
+            };
+        }
+
+        let retry_after = match err {
+            Error::RateLimited { retry_after } => *retry_after,
+            Error::ServerError {
+                status,
+                retry_after,
+            } if is_transient_server_error(*status) => *retry_after,
+            Error::Timeout => None,
+            Error::ReqwestError(err) if err.is_connect() => None,
+            _ => return None,
+        };
+
+        match retry_after {
+            Some(delay) if delay > self.max_backoff => None,
+            Some(delay) => Some(delay),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+
+    /// Computes the exponential backoff for the given attempt, with jitter applied to the
+    /// upper half of the interval so that concurrent clients don't retry in lockstep.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+
+        let half = exp / 2;
+        let jitter = rand::thread_rng().gen_range(0..=half.as_millis() as u64);
+        half + Duration::from_millis(jitter)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Server errors that usually indicate the request was never processed, and thus safe to retry.
+fn is_transient_server_error(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Parses the `Retry-After` header, which is either a number of seconds or an HTTP date.
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+fn map_reqwest_error(err: reqwest::Error) -> Error {
+    if err.is_timeout() {
+        Error::Timeout
+    } else {
+        Error::ReqwestError(err)
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub struct GraphQLErrors(Vec<graphql_client::Error>);
 
@@ -125,3 +779,459 @@ impl fmt::Display for GraphQLErrors {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use assert_matches::assert_matches;
+    use axum::extract::State;
+    use axum::http::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Response};
+    use axum::routing::post;
+    use axum::{Form, Json, Router};
+    use serde_json::{json, Value};
+    use url::Url;
+
+    use super::{Client, Operation, RetryPolicy, DEVICE_CODE_GRANT_TYPE};
+    use crate::credential::AccessToken;
+    use crate::Error;
+
+    const FAST_RETRIES: RetryPolicy = RetryPolicy {
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(20),
+    };
+
+    /// Serves `router` on a random local port and returns its base url.
+    async fn serve(router: Router) -> Url {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        Url::parse(&format!("http://{addr}")).unwrap()
+    }
+
+    /// Starts a stand-in API server whose `/query` endpoint answers with `responder`, which is
+    /// given the (zero-based) number of the incoming request. Returns the server's base url and
+    /// the request counter.
+    async fn stand_in_server<F>(responder: F) -> (Url, Arc<AtomicUsize>)
+    where
+        F: Fn(usize) -> Response + Clone + Send + Sync + 'static,
+    {
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let handler = |State((counter, responder)): State<(Arc<AtomicUsize>, F)>| async move {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            responder(n)
+        };
+
+        let router = Router::new()
+            .route("/query", post(handler))
+            .with_state((counter.clone(), responder));
+
+        (serve(router).await, counter)
+    }
+
+    fn access_token() -> AccessToken {
+        AccessToken {
+            token: "mytoken".to_string(),
+            r#type: "Bearer".to_string(),
+            expires_at: None,
+            refresh_token: None,
+        }
+    }
+
+    fn client(base_url: Url) -> Client {
+        Client::builder()
+            .base_url(base_url)
+            .access_token(access_token())
+            .retry_policy(FAST_RETRIES)
+            .build()
+            .unwrap()
+    }
+
+    fn ok_response() -> Response {
+        Json(json!({ "data": { "ok": true } })).into_response()
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors() {
+        let (url, counter) = stand_in_server(|n| match n {
+            0 => StatusCode::BAD_GATEWAY.into_response(),
+            1 => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+            _ => ok_response(),
+        })
+        .await;
+
+        let res: Value = client(url).query(&json!({})).await.unwrap();
+
+        assert_eq!(res, json!({ "ok": true }));
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (url, counter) =
+            stand_in_server(|_| StatusCode::SERVICE_UNAVAILABLE.into_response()).await;
+
+        let err = client(url).query::<Value, _>(&json!({})).await.unwrap_err();
+
+        assert_matches!(
+            err,
+            Error::ServerError {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                ..
+            }
+        );
+        assert_eq!(counter.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_internal_server_error() {
+        let (url, counter) =
+            stand_in_server(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response()).await;
+
+        let err = client(url).query::<Value, _>(&json!({})).await.unwrap_err();
+
+        assert_matches!(err, Error::ServerError { .. });
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_honors_retry_after() {
+        let (url, counter) = stand_in_server(|n| match n {
+            0 => (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, "0")]).into_response(),
+            _ => ok_response(),
+        })
+        .await;
+
+        let res: Value = client(url).query(&json!({})).await.unwrap();
+
+        assert_eq!(res, json!({ "ok": true }));
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_with_long_retry_after_is_not_retried() {
+        let (url, counter) = stand_in_server(|_| {
+            (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, "120")]).into_response()
+        })
+        .await;
+
+        let err = client(url).query::<Value, _>(&json!({})).await.unwrap_err();
+
+        assert_matches!(err, Error::RateLimited { retry_after: Some(d) } if d == Duration::from_secs(120));
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn request_timeout() {
+        let handler = || async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            ok_response()
+        };
+        let url = serve(Router::new().route("/query", post(handler))).await;
+
+        let client = Client::builder()
+            .base_url(url)
+            .access_token(access_token())
+            .retry_policy(RetryPolicy::none())
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let err = client.query::<Value, _>(&json!({})).await.unwrap_err();
+
+        assert_matches!(err, Error::Timeout);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_mutations() {
+        let (url, counter) = stand_in_server(|_| StatusCode::GATEWAY_TIMEOUT.into_response()).await;
+
+        let body = json!({ "query": "# Deletes a deployment\nmutation DeleteDeployment { ok }" });
+        let err = client(url).query::<Value, _>(&body).await.unwrap_err();
+
+        assert_matches!(err, Error::ServerError { .. });
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn waiting_mutation_has_no_timeout() {
+        let handler = || async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            ok_response()
+        };
+        let url = serve(Router::new().route("/query", post(handler))).await;
+
+        let client = Client::builder()
+            .base_url(url)
+            .access_token(access_token())
+            .retry_policy(RetryPolicy::none())
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let body = json!({
+            "query": "mutation UpdateDeployment($wait: Boolean) { ok }",
+            "variables": { "wait": true },
+        });
+        let res: Value = client.query(&body).await.unwrap();
+        assert_eq!(res, json!({ "ok": true }));
+
+        let body = json!({
+            "query": "mutation UpdateDeployment($wait: Boolean) { ok }",
+            "variables": { "wait": false },
+        });
+        let err = client.query::<Value, _>(&body).await.unwrap_err();
+        assert_matches!(err, Error::Timeout);
+    }
+
+    #[test]
+    fn classify_operations() {
+        let query = Operation::of(&json!({ "query": "query Me { me { id } }" }));
+        assert_eq!(query, Operation::default());
+
+        let commented = json!({ "query": "# mutation in a comment\nquery Me { me { id } }" });
+        assert!(!Operation::of(&commented).is_mutation);
+
+        let mutation = json!({ "query": "mutation Create { ok }", "variables": { "wait": true } });
+        assert_eq!(
+            Operation::of(&mutation),
+            Operation {
+                is_mutation: true,
+                waits: true
+            }
+        );
+
+        assert_eq!(Operation::of(&json!({})), Operation::default());
+    }
+
+    #[tokio::test]
+    async fn missing_data() {
+        let (url, _) = stand_in_server(|_| Json(json!({ "data": null })).into_response()).await;
+
+        let err = client(url).query::<Value, _>(&json!({})).await.unwrap_err();
+        assert_matches!(err, Error::MissingData);
+    }
+
+    #[tokio::test]
+    async fn builder_sends_configured_headers() {
+        let handler = |headers: HeaderMap| async move {
+            let header = |name: &str| headers.get(name).unwrap().to_str().unwrap().to_owned();
+            Json(json!({ "data": {
+                "userAgent": header("user-agent"),
+                "team": header("x-team"),
+                "authorization": header("authorization"),
+            } }))
+        };
+        let url = serve(Router::new().route("/query", post(handler))).await;
+
+        let client = Client::builder()
+            .base_url(url)
+            .access_token(access_token())
+            .user_agent("sozo/1.0.0")
+            .header("x-team", HeaderValue::from_static("dojo"))
+            .build()
+            .unwrap();
+
+        let res: Value = client.query(&json!({})).await.unwrap();
+
+        let user_agent = format!("sozo/1.0.0 slot/{}", env!("CARGO_PKG_VERSION"));
+        assert_eq!(
+            res,
+            json!({ "userAgent": user_agent, "team": "dojo", "authorization": "Bearer mytoken" })
+        );
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_query_fails_locally() {
+        let (url, counter) = stand_in_server(|_| ok_response()).await;
+
+        let client = Client::builder().base_url(url).build().unwrap();
+        let err = client.query::<Value, _>(&json!({})).await.unwrap_err();
+
+        assert_matches!(err, Error::Unauthorized);
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn anonymous_query_has_no_authorization() {
+        let handler = |headers: HeaderMap| async move {
+            let authorized = headers.contains_key("authorization");
+            Json(json!({ "data": { "authorized": authorized } }))
+        };
+        let url = serve(Router::new().route("/query", post(handler))).await;
+
+        let client = Client::builder().base_url(url).anonymous().build().unwrap();
+        let res: Value = client.query(&json!({})).await.unwrap();
+
+        assert_eq!(res, json!({ "authorized": false }));
+    }
+
+    /// Starts a stand-in OAuth server for the device authorization flow, whose token endpoint
+    /// answers with `token_responses` in order.
+    async fn device_flow_server(token_responses: Vec<Response>) -> Url {
+        let device_code = || async {
+            Json(json!({
+                "device_code": "devicecode",
+                "user_code": "ABCD-EFGH",
+                "verification_uri": "https://x.cartridge.gg/device",
+                "expires_in": 60,
+                "interval": 0
+            }))
+        };
+
+        let responses = Arc::new(std::sync::Mutex::new(token_responses.into_iter()));
+        let token = move |Form(form): Form<HashMap<String, String>>| async move {
+            assert_eq!(form["grant_type"], DEVICE_CODE_GRANT_TYPE);
+            assert_eq!(form["device_code"], "devicecode");
+            responses.lock().unwrap().next().expect("unexpected poll")
+        };
+
+        let router = Router::new()
+            .route("/oauth2/device/code", post(device_code))
+            .route("/oauth2/token", post(token));
+
+        serve(router).await
+    }
+
+    fn oauth_error(error: &str) -> Response {
+        (StatusCode::BAD_REQUEST, Json(json!({ "error": error }))).into_response()
+    }
+
+    #[tokio::test]
+    async fn device_flow_polls_until_approved() {
+        let url = device_flow_server(vec![
+            oauth_error("authorization_pending"),
+            StatusCode::BAD_GATEWAY.into_response(),
+            Json(json!({ "access_token": "mytoken", "token_type": "Bearer" })).into_response(),
+        ])
+        .await;
+
+        let client = Client::builder().base_url(url).build().unwrap();
+        let authorization = client.device_authorization().await.unwrap();
+        assert_eq!(authorization.user_code, "ABCD-EFGH");
+
+        let token = client.poll_device_token(&authorization).await.unwrap();
+        assert_eq!(token, access_token());
+    }
+
+    #[tokio::test]
+    async fn device_flow_denied() {
+        let url = device_flow_server(vec![
+            oauth_error("authorization_pending"),
+            oauth_error("access_denied"),
+        ])
+        .await;
+
+        let client = Client::builder().base_url(url).build().unwrap();
+        let authorization = client.device_authorization().await.unwrap();
+
+        let err = client.poll_device_token(&authorization).await.unwrap_err();
+        assert_matches!(err, Error::LoginDenied);
+    }
+
+    /// Starts a stand-in API server that only accepts the `newtoken` access token, and whose
+    /// token endpoint refreshes the `refreshtoken` refresh token. Returns the server's base url
+    /// and the number of refreshes.
+    async fn refresh_server() -> (Url, Arc<AtomicUsize>) {
+        let refreshes = Arc::new(AtomicUsize::new(0));
+
+        let query = |headers: HeaderMap| async move {
+            match headers.get("authorization").and_then(|v| v.to_str().ok()) {
+                Some("Bearer newtoken") => ok_response(),
+                _ => StatusCode::FORBIDDEN.into_response(),
+            }
+        };
+
+        let counter = refreshes.clone();
+        let token = move |Form(form): Form<HashMap<String, String>>| async move {
+            assert_eq!(form["grant_type"], "refresh_token");
+            assert_eq!(form["refresh_token"], "refreshtoken");
+            counter.fetch_add(1, Ordering::SeqCst);
+
+            Json(json!({
+                "access_token": "newtoken",
+                "token_type": "Bearer",
+                "expires_in": 3600
+            }))
+        };
+
+        let router = Router::new()
+            .route("/query", post(query))
+            .route("/oauth2/token", post(token));
+
+        (serve(router).await, refreshes)
+    }
+
+    #[tokio::test]
+    async fn refresh_rejected_token() {
+        let (url, refreshes) = refresh_server().await;
+
+        let token = AccessToken {
+            refresh_token: Some("refreshtoken".to_string()),
+            ..access_token()
+        };
+
+        let client = Client::builder()
+            .base_url(url)
+            .access_token(token)
+            .build()
+            .unwrap();
+
+        let res: Value = client.query(&json!({})).await.unwrap();
+        assert_eq!(res, json!({ "ok": true }));
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+
+        let refreshed = client.token().await.unwrap();
+        assert_eq!(refreshed.token, "newtoken");
+        assert_eq!(refreshed.refresh_token.as_deref(), Some("refreshtoken"));
+        assert!(refreshed.expires_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn refresh_expiring_token() {
+        let (url, refreshes) = refresh_server().await;
+
+        let token = AccessToken {
+            expires_at: Some(0),
+            refresh_token: Some("refreshtoken".to_string()),
+            ..access_token()
+        };
+
+        let client = Client::builder()
+            .base_url(url)
+            .access_token(token)
+            .build()
+            .unwrap();
+
+        let _: Value = client.query(&json!({})).await.unwrap();
+        let _: Value = client.query(&json!({})).await.unwrap();
+
+        // the token is refreshed before the first query, and only once
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rejected_token_without_refresh_token() {
+        let (url, refreshes) = refresh_server().await;
+
+        let err = client(url).query::<Value, _>(&json!({})).await.unwrap_err();
+
+        assert_matches!(err, Error::InvalidOAuth);
+        assert_eq!(refreshes.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn backoff_is_bounded() {
+        for attempt in 0..10 {
+            let delay = FAST_RETRIES.backoff(attempt);
+            assert!(delay <= FAST_RETRIES.max_backoff);
+        }
+    }
+}
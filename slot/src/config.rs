@@ -0,0 +1,471 @@
+//! The TOML configuration files of the deployed services.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::{Context, Result};
+use toml::{Table, Value};
+
+use self::schema::{Kind, Schema};
+use crate::manifest::Service;
+use crate::read;
+
+mod schema;
+
+/// The configuration keys whose change resets the storage of a Katana deployment, along with
+/// the keys nested under them.
+const KATANA_STORAGE_KEYS: &[&str] = &[
+    "starknet.env.chain_id",
+    "starknet.genesis",
+    "forking",
+    "development.seed",
+    "development.total_accounts",
+];
+
+/// The configuration keys whose change resets the storage of a Torii deployment, i.e. requires
+/// re-indexing the world.
+const TORII_STORAGE_KEYS: &[&str] = &["world_address", "rpc", "indexing.contracts"];
+
+/// Decodes a configuration file as returned by the API, which may be base64 encoded.
+pub fn decode(config_file: &str) -> String {
+    if config_file.parse::<Table>().is_err() {
+        if let Ok(decoded) = read::base64_decode_string(config_file.trim()) {
+            if decoded.parse::<Table>().is_ok() {
+                return decoded;
+            }
+        }
+    }
+
+    config_file.to_string()
+}
+
+/// Compares two configuration files by their values, ignoring formatting and comments.
+pub fn same_config(a: &str, b: &str) -> bool {
+    match (a.parse::<Table>(), b.parse::<Table>()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a.trim() == b.trim(),
+    }
+}
+
+/// A difference between two configuration files, by dotted key, e.g. `indexing.pending`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    Added { key: String, value: Value },
+    Removed { key: String, value: Value },
+    Changed { key: String, from: Value, to: Value },
+}
+
+impl ConfigChange {
+    pub fn key(&self) -> &str {
+        match self {
+            ConfigChange::Added { key, .. }
+            | ConfigChange::Removed { key, .. }
+            | ConfigChange::Changed { key, .. } => key,
+        }
+    }
+
+    /// Whether applying the change resets the storage of the deployment.
+    pub fn resets_storage(&self, service: Service) -> bool {
+        let keys = match service {
+            Service::Katana => KATANA_STORAGE_KEYS,
+            Service::Torii => TORII_STORAGE_KEYS,
+        };
+
+        let key = self.key();
+        keys.iter().any(|k| {
+            key == *k || key.starts_with(&format!("{k}.")) || k.starts_with(&format!("{key}."))
+        })
+    }
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigChange::Added { key, value } => write!(f, "+ {key} = {value}"),
+            ConfigChange::Removed { key, value } => write!(f, "- {key} = {value}"),
+            ConfigChange::Changed { key, from, to } => write!(f, "~ {key} = {from} -> {to}"),
+        }
+    }
+}
+
+/// Returns the differences between two configuration files, ordered by key. Tables are compared
+/// key by key, other values, including arrays, as a whole.
+pub fn diff(from: &str, to: &str) -> Result<Vec<ConfigChange>> {
+    let from = flatten(&from.parse().context("Invalid current config")?);
+    let to = flatten(&to.parse().context("Invalid new config")?);
+
+    let mut changes = Vec::new();
+    for (key, value) in &from {
+        match to.get(key) {
+            None => changes.push(ConfigChange::Removed {
+                key: key.clone(),
+                value: value.clone(),
+            }),
+            Some(new) if new != value => changes.push(ConfigChange::Changed {
+                key: key.clone(),
+                from: value.clone(),
+                to: new.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (key, value) in to {
+        if !from.contains_key(&key) {
+            changes.push(ConfigChange::Added { key, value });
+        }
+    }
+
+    changes.sort_by(|a, b| a.key().cmp(b.key()));
+    Ok(changes)
+}
+
+fn flatten(table: &Table) -> BTreeMap<String, Value> {
+    fn visit(prefix: &str, table: &Table, values: &mut BTreeMap<String, Value>) {
+        for (key, value) in table {
+            let key = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+
+            match value {
+                Value::Table(table) => visit(&key, table, values),
+                value => {
+                    values.insert(key, value.clone());
+                }
+            }
+        }
+    }
+
+    let mut values = BTreeMap::new();
+    visit("", table, &mut values);
+    values
+}
+
+/// The options a Katana deployment is created with, besides its configuration file.
+#[derive(Debug, Clone, Default)]
+pub struct KatanaOptions {
+    pub provable: bool,
+    pub network: Option<String>,
+    pub saya: bool,
+    pub optimistic: bool,
+    pub fork_provider_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The configuration may not work as intended, e.g. an unknown key that is ignored.
+    Warning,
+    /// The configuration can't be deployed.
+    Error,
+}
+
+/// An issue found by validating a configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// The dotted key the issue is about, or the name of the option.
+    pub key: String,
+    pub message: String,
+}
+
+impl Issue {
+    fn warning(key: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            key: key.to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn error(key: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            key: key.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{severity}: `{}` {}", self.key, self.message)
+    }
+}
+
+/// Validates a configuration file against the known keys of the service.
+///
+/// Unknown keys are reported as warnings, as they may have been added by a newer version of the
+/// service, and values of the wrong type as errors.
+pub fn validate(service: Service, config: &str) -> Result<Vec<Issue>> {
+    let table = config
+        .parse::<Table>()
+        .with_context(|| format!("Invalid {service} config"))?;
+
+    let schema = match service {
+        Service::Katana => schema::KATANA,
+        Service::Torii => schema::TORII,
+    };
+
+    let mut issues = Vec::new();
+    check_table("", &table, schema, &mut issues);
+
+    if service == Service::Katana
+        && table.get("no_mining") == Some(&Value::Boolean(true))
+        && table.contains_key("block_time")
+    {
+        issues.push(Issue::error("block_time", "conflicts with `no_mining`"));
+    }
+
+    Ok(issues)
+}
+
+/// Validates the options of a Katana deployment, along with its configuration file if any.
+pub fn validate_katana_options(options: &KatanaOptions, config: Option<&str>) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    if options.optimistic && options.fork_provider_url.is_some() {
+        issues.push(Issue::error(
+            "fork_provider_url",
+            "conflicts with the optimistic mode",
+        ));
+    }
+
+    let forking = config
+        .and_then(|config| config.parse::<Table>().ok())
+        .is_some_and(|table| {
+            table
+                .get("forking")
+                .and_then(|forking| forking.get("fork_provider"))
+                .is_some()
+        });
+    if options.optimistic && forking {
+        issues.push(Issue::error(
+            "forking.fork_provider",
+            "conflicts with the optimistic mode",
+        ));
+    }
+
+    if !options.provable {
+        if options.network.is_some() {
+            issues.push(Issue::error(
+                "network",
+                "is only supported in provable mode",
+            ));
+        }
+        if options.saya {
+            issues.push(Issue::error("saya", "is only supported in provable mode"));
+        }
+    }
+
+    issues
+}
+
+fn check_table(prefix: &str, table: &Table, schema: Schema, issues: &mut Vec<Issue>) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+
+        match schema.iter().find(|(name, _)| name == key) {
+            Some((_, kind)) => check_value(&path, value, kind, issues),
+            None => {
+                let message = match suggest(key, schema) {
+                    Some(suggestion) => format!("is unknown, did you mean `{suggestion}`?"),
+                    None => "is unknown".to_string(),
+                };
+                issues.push(Issue::warning(&path, message));
+            }
+        }
+    }
+}
+
+fn check_value(path: &str, value: &Value, kind: &Kind, issues: &mut Vec<Issue>) {
+    let expected = match (kind, value) {
+        (Kind::Any, _)
+        | (Kind::Bool, Value::Boolean(_))
+        | (Kind::Integer, Value::Integer(_))
+        | (Kind::String, Value::String(_)) => return,
+        (Kind::Array(kind), Value::Array(values)) => {
+            for (i, value) in values.iter().enumerate() {
+                check_value(&format!("{path}[{i}]"), value, kind, issues);
+            }
+            return;
+        }
+        (Kind::Table(schema), Value::Table(table)) => {
+            check_table(path, table, schema, issues);
+            return;
+        }
+        (Kind::Bool, _) => "a boolean",
+        (Kind::Integer, _) => "an integer",
+        (Kind::String, _) => "a string",
+        (Kind::Array(_), _) => "an array",
+        (Kind::Table(_), _) => "a table",
+    };
+
+    issues.push(Issue::error(
+        path,
+        format!("must be {expected}, found {}", value.type_str()),
+    ));
+}
+
+/// Returns the known key closest to an unknown one, if close enough to be a typo.
+fn suggest<'a>(key: &str, schema: &[(&'a str, Kind)]) -> Option<&'a str> {
+    schema
+        .iter()
+        .map(|(name, _)| (*name, edit_distance(key, name)))
+        .filter(|(name, distance)| *distance <= (name.len() / 3).max(2))
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(name, _)| name)
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        world_address = "0x1"
+
+        [indexing]
+        pending = true
+        contracts = ["erc20:0x2"]
+    "#;
+
+    #[test]
+    fn diff_configs() {
+        let new = r#"
+            world_address = "0x1" # same value
+            [indexing]
+            pending = false
+            [sql]
+            historical = ["ns-Moved"]
+        "#;
+
+        let changes = diff(CONFIG, new).unwrap();
+        let lines = changes.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "- indexing.contracts = [\"erc20:0x2\"]",
+                "~ indexing.pending = true -> false",
+                "+ sql.historical = [\"ns-Moved\"]",
+            ]
+        );
+
+        assert!(changes[0].resets_storage(Service::Torii));
+        assert!(!changes[1].resets_storage(Service::Torii));
+        assert!(!changes[0].resets_storage(Service::Katana));
+        assert!(diff(CONFIG, CONFIG).unwrap().is_empty());
+        assert!(diff(CONFIG, "invalid").is_err());
+    }
+
+    #[test]
+    fn resets_storage_nested_keys() {
+        let change = |key: &str| ConfigChange::Added {
+            key: key.to_string(),
+            value: Value::Boolean(true),
+        };
+
+        assert!(change("development.seed").resets_storage(Service::Katana));
+        assert!(change("forking.fork_block").resets_storage(Service::Katana));
+        assert!(!change("development.no_fee").resets_storage(Service::Katana));
+        // a value replacing a table holding storage-resetting keys
+        assert!(change("development").resets_storage(Service::Katana));
+    }
+
+    #[test]
+    fn decode_config() {
+        let encoded = read::base64_encode_string(CONFIG);
+        assert_eq!(decode(&encoded), CONFIG);
+        assert_eq!(decode(CONFIG), CONFIG);
+        assert!(same_config(
+            CONFIG,
+            "world_address = \"0x1\"\nindexing = { pending = true, contracts = [\"erc20:0x2\"] }"
+        ));
+    }
+
+    #[test]
+    fn validate_unknown_keys_and_types() {
+        let config = r#"
+            world_address = "0x1"
+            rcp = "https://api.cartridge.gg/x/starknet/sepolia"
+            [indexing]
+            pending = "yes"
+            contracts = ["erc20:0x2", 3]
+            [unrelated]
+            key = 1
+        "#;
+
+        let issues = validate(Service::Torii, config).unwrap();
+        let lines = issues.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "error: `indexing.contracts[1]` must be a string, found integer",
+                "error: `indexing.pending` must be a boolean, found string",
+                "warning: `rcp` is unknown, did you mean `rpc`?",
+                "warning: `unrelated` is unknown",
+            ]
+        );
+
+        assert!(validate(Service::Torii, CONFIG).unwrap().is_empty());
+        assert!(validate(Service::Torii, "world_address = ").is_err());
+    }
+
+    #[test]
+    fn validate_katana_conflicts() {
+        let issues = validate(Service::Katana, "no_mining = true\nblock_time = 1000").unwrap();
+        assert_eq!(
+            issues,
+            [Issue::error("block_time", "conflicts with `no_mining`")]
+        );
+
+        let options = KatanaOptions {
+            optimistic: true,
+            fork_provider_url: Some("https://rpc".to_string()),
+            saya: true,
+            ..Default::default()
+        };
+        let config = "[forking]\nfork_provider = \"https://rpc\"";
+        let keys = validate_katana_options(&options, Some(config))
+            .into_iter()
+            .map(|i| i.key)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, ["fork_provider_url", "forking.fork_provider", "saya"]);
+
+        assert!(validate_katana_options(&KatanaOptions::default(), None).is_empty());
+    }
+
+    #[test]
+    fn suggestions() {
+        let schema: Schema = &[("pending", Kind::Bool), ("polling_interval", Kind::Integer)];
+        assert_eq!(suggest("pendng", schema), Some("pending"));
+        assert_eq!(suggest("poling_interval", schema), Some("polling_interval"));
+        assert_eq!(suggest("contracts", schema), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
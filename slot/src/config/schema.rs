@@ -0,0 +1,164 @@
+//! The known keys of the Katana and Torii configuration files.
+//!
+//! The library doesn't depend on Katana or Torii, so these tables are maintained by hand rather
+//! than derived from their config types. `KATANA` mirrors the config file of Katana at the
+//! revision of `katana-primitives` pinned by the CLI (`d615ff05`), and `TORII` the config file
+//! of the Torii versions deployed by Slot at that time. Keys added by later versions are reported
+//! as unknown until they are added here, and the sections whose layout differs between versions
+//! accept any value. `starknet.env.chain_id` and `starknet.genesis` are only checked to be
+//! strings, Katana parsing them when it starts.
+
+/// The type of the value of a configuration key.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Kind {
+    /// Any value. Used for the sections whose contents depend on the version of the service.
+    Any,
+    Bool,
+    Integer,
+    String,
+    Array(&'static Kind),
+    Table(Schema),
+}
+
+pub(super) type Schema = &'static [(&'static str, Kind)];
+
+use Kind::*;
+
+const METRICS: Schema = &[
+    ("metrics", Bool),
+    ("metrics_addr", String),
+    ("metrics_port", Integer),
+];
+
+pub(super) const KATANA: Schema = &[
+    ("no_mining", Bool),
+    ("block_time", Integer),
+    ("block_cairo_steps_limit", Integer),
+    ("db_dir", String),
+    ("messaging", Any),
+    ("logging", Any),
+    ("explorer", Any),
+    (
+        "starknet",
+        Table(&[
+            (
+                "env",
+                Table(&[
+                    ("chain_id", String),
+                    ("validate_max_steps", Integer),
+                    ("invoke_max_steps", Integer),
+                ]),
+            ),
+            ("genesis", String),
+        ]),
+    ),
+    (
+        "gpo",
+        Table(&[
+            ("l1_eth_gas_price", Any),
+            ("l1_strk_gas_price", Any),
+            ("l1_eth_data_gas_price", Any),
+            ("l1_strk_data_gas_price", Any),
+        ]),
+    ),
+    (
+        "forking",
+        Table(&[("fork_provider", String), ("fork_block", Any)]),
+    ),
+    (
+        "development",
+        Table(&[
+            ("seed", String),
+            ("total_accounts", Integer),
+            ("no_fee", Bool),
+            ("no_account_validation", Bool),
+        ]),
+    ),
+    (
+        "server",
+        Table(&[
+            ("http_addr", String),
+            ("http_port", Integer),
+            ("http_cors_origins", Array(&String)),
+            ("http_modules", Array(&String)),
+            ("max_connections", Integer),
+            ("max_request_body_size", Integer),
+            ("max_response_body_size", Integer),
+            ("max_event_page_size", Integer),
+            ("max_proof_keys", Integer),
+            ("max_call_gas", Integer),
+        ]),
+    ),
+    ("metrics", Table(METRICS)),
+    (
+        "cartridge",
+        Table(&[("controllers", Bool), ("paymaster", Bool), ("api", String)]),
+    ),
+];
+
+pub(super) const TORII: Schema = &[
+    ("world_address", String),
+    ("rpc", String),
+    ("db_dir", String),
+    ("explorer", Bool),
+    ("snapshot", Any),
+    ("runner", Any),
+    ("grpc", Any),
+    (
+        "indexing",
+        Table(&[
+            ("events_chunk_size", Integer),
+            ("blocks_chunk_size", Integer),
+            ("pending", Bool),
+            ("polling_interval", Integer),
+            ("max_concurrent_tasks", Integer),
+            ("transactions", Bool),
+            ("contracts", Array(&String)),
+            ("namespaces", Array(&String)),
+            ("world_block", Integer),
+            ("controllers", Bool),
+            ("strict_model_reader", Bool),
+            ("batch_chunk_size", Integer),
+        ]),
+    ),
+    (
+        "events",
+        Table(&[("raw", Bool), ("historical", Array(&String))]),
+    ),
+    (
+        "sql",
+        Table(&[
+            ("all_model_indices", Bool),
+            ("model_indices", Array(&Any)),
+            ("historical", Array(&String)),
+            ("page_size", Integer),
+            ("cache_size", Integer),
+            ("hooks", Array(&Any)),
+            ("migrations", String),
+        ]),
+    ),
+    (
+        "erc",
+        Table(&[("max_metadata_tasks", Integer), ("artifacts_path", String)]),
+    ),
+    (
+        "server",
+        Table(&[
+            ("http_addr", String),
+            ("http_port", Integer),
+            ("http_cors_origins", Array(&String)),
+        ]),
+    ),
+    (
+        "relay",
+        Table(&[
+            ("port", Integer),
+            ("webrtc_port", Integer),
+            ("websocket_port", Integer),
+            ("local_key_path", String),
+            ("cert_path", String),
+            ("peers", Array(&String)),
+        ]),
+    ),
+    ("metrics", Table(METRICS)),
+];
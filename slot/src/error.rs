@@ -1,5 +1,8 @@
+use std::time::Duration;
+
 use crate::api::{self};
 use account_sdk::signers::SignError;
+use reqwest::StatusCode;
 use starknet::core::utils::NonAsciiNameError;
 
 #[derive(Debug, thiserror::Error)]
@@ -19,6 +22,15 @@ pub enum Error {
     #[error("Invalid OAuth token, please authenticate with `slot auth login`")]
     InvalidOAuth,
 
+    #[error("Login request denied")]
+    LoginDenied,
+
+    #[error("Login request expired, please try again")]
+    LoginExpired,
+
+    #[error("Invalid passphrase")]
+    InvalidPassphrase,
+
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
 
@@ -31,6 +43,31 @@ pub enum Error {
     #[error(transparent)]
     Signing(#[from] SignError),
 
+    #[error("Session expired at {expires_at}, please create a new one")]
+    SessionExpired { expires_at: u64 },
+
     #[error(transparent)]
     Api(#[from] api::GraphQLErrors),
+
+    #[error("API rate limit exceeded{}", fmt_retry_after(.retry_after))]
+    RateLimited { retry_after: Option<Duration> },
+
+    #[error("API server error: {status}{}", fmt_retry_after(.retry_after))]
+    ServerError {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
+
+    #[error("API request timed out")]
+    Timeout,
+
+    #[error("API response is missing data")]
+    MissingData,
+}
+
+fn fmt_retry_after(retry_after: &Option<Duration>) -> String {
+    match retry_after {
+        Some(delay) => format!(", retry after {}s", delay.as_secs()),
+        None => String::new(),
+    }
 }
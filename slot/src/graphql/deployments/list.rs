@@ -1,3 +1,4 @@
+use crate::graphql::deployments::Time;
 use graphql_client::GraphQLQuery;
 
 #[derive(GraphQLQuery)]
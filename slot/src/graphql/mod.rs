@@ -3,6 +3,7 @@ pub mod deployments;
 pub mod merkle_drop;
 pub mod paymaster;
 pub mod rpc;
+pub mod session;
 pub mod team;
 
 pub use graphql_client::{GraphQLQuery, Response};
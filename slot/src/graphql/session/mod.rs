@@ -0,0 +1,22 @@
+use graphql_client::GraphQLQuery;
+use starknet::core::types::Felt;
+
+pub type Time = String;
+pub type Cursor = String;
+pub type Long = u64;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.json",
+    query_path = "src/graphql/session/sessions.graphql",
+    response_derives = "Debug, Clone, Serialize"
+)]
+pub struct Sessions;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.json",
+    query_path = "src/graphql/session/revoke.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct RevokeSessions;
@@ -4,10 +4,14 @@ pub mod account;
 pub mod api;
 pub mod bigint;
 pub mod browser;
+pub mod config;
 pub mod credential;
+pub mod encryption;
 pub(crate) mod error;
 pub mod graphql;
+pub mod manifest;
 pub mod preset;
+pub mod profile;
 pub mod read;
 pub mod server;
 pub mod session;
@@ -0,0 +1,556 @@
+//! The `slot.toml` project manifest, declaring the deployments of a project.
+//!
+//! ```toml
+//! # The team owning the deployments created from the manifest.
+//! team = "my-team"
+//!
+//! [deployments.my-game.katana]
+//! tier = "pro"
+//! regions = ["us-east"]
+//! config = "katana.toml"
+//!
+//! [deployments.my-game.torii]
+//! version = "v1.6.0"
+//! replicas = 2
+//! observability = true
+//! config = "torii.toml"
+//! ```
+//!
+//! [`plan`] compares a deployment declared in the manifest with its current state, to find what
+//! has to be created or updated.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::graphql::deployments::{create_deployment, update_deployment};
+use crate::{config, version};
+
+/// The default file name of the manifest.
+pub const MANIFEST_FILE_NAME: &str = "slot.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// The team owning the deployments created from the manifest. Defaults to a team named after
+    /// the user.
+    pub team: Option<String>,
+    /// The deployments, by project name.
+    #[serde(default)]
+    pub deployments: BTreeMap<String, ProjectManifest>,
+}
+
+/// The services deployed for a project.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectManifest {
+    pub katana: Option<ServiceManifest>,
+    pub torii: Option<ServiceManifest>,
+}
+
+/// A deployed service. The fields left unset are not managed by the manifest.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceManifest {
+    /// The tier of the deployment. Deployments are created on the basic tier by default.
+    pub tier: Option<Tier>,
+    pub regions: Option<Vec<String>>,
+    pub version: Option<String>,
+    /// The number of replicas. Torii only.
+    pub replicas: Option<i64>,
+    pub observability: Option<bool>,
+    /// The path of the configuration file, relative to the manifest.
+    pub config: Option<PathBuf>,
+
+    /// Enable database replication using litestream. Torii only, on creation.
+    #[serde(default)]
+    pub replication: bool,
+
+    /// Run Katana in provable mode. Katana only, on creation.
+    #[serde(default)]
+    pub provable: bool,
+    /// The network of a provable Katana. Katana only, on creation.
+    pub network: Option<String>,
+    /// Start a saya instance alongside a provable Katana. Katana only, on creation.
+    #[serde(default)]
+    pub saya: bool,
+    /// Run Katana in optimistic mode. Katana only, on creation.
+    #[serde(default)]
+    pub optimistic: bool,
+    /// The URL of the fork provider. Katana only, on creation.
+    pub fork_provider_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    #[default]
+    Basic,
+    Pro,
+    Epic,
+    Legendary,
+}
+
+impl Tier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Tier::Basic => "basic",
+            Tier::Pro => "pro",
+            Tier::Epic => "epic",
+            Tier::Legendary => "legendary",
+        }
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<Tier> for create_deployment::DeploymentTier {
+    fn from(tier: Tier) -> Self {
+        match tier {
+            Tier::Basic => Self::basic,
+            Tier::Pro => Self::pro,
+            Tier::Epic => Self::epic,
+            Tier::Legendary => Self::legendary,
+        }
+    }
+}
+
+impl From<Tier> for update_deployment::DeploymentTier {
+    fn from(tier: Tier) -> Self {
+        match tier {
+            Tier::Basic => Self::basic,
+            Tier::Pro => Self::pro,
+            Tier::Epic => Self::epic,
+            Tier::Legendary => Self::legendary,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Service {
+    Katana,
+    Torii,
+}
+
+impl fmt::Display for Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Service::Katana => write!(f, "katana"),
+            Service::Torii => write!(f, "torii"),
+        }
+    }
+}
+
+impl Manifest {
+    /// Loads the manifest at `path`. The paths of the configuration files are resolved relative
+    /// to the manifest.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the manifest {}", path.display()))?;
+
+        let mut manifest = Self::parse(&contents)
+            .with_context(|| format!("Invalid manifest {}", path.display()))?;
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for (_, _, service) in manifest.services_mut() {
+            if let Some(config) = &mut service.config {
+                *config = dir.join(&*config);
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Parses and validates a manifest.
+    pub fn parse(contents: &str) -> Result<Self> {
+        let manifest: Self = toml::from_str(contents)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Returns the declared services, ordered by project and service.
+    pub fn services(&self) -> impl Iterator<Item = (&str, Service, &ServiceManifest)> {
+        self.deployments.iter().flat_map(|(project, manifest)| {
+            let katana = manifest.katana.as_ref().map(|s| (Service::Katana, s));
+            let torii = manifest.torii.as_ref().map(|s| (Service::Torii, s));
+            katana
+                .into_iter()
+                .chain(torii)
+                .map(move |(service, manifest)| (project.as_str(), service, manifest))
+        })
+    }
+
+    fn services_mut(&mut self) -> impl Iterator<Item = (&str, Service, &mut ServiceManifest)> {
+        self.deployments.iter_mut().flat_map(|(project, manifest)| {
+            let katana = manifest.katana.as_mut().map(|s| (Service::Katana, s));
+            let torii = manifest.torii.as_mut().map(|s| (Service::Torii, s));
+            katana
+                .into_iter()
+                .chain(torii)
+                .map(move |(service, manifest)| (project.as_str(), service, manifest))
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (project, service, manifest) in self.services() {
+            let only = |option: &str, expected: Service| -> Result<()> {
+                if service != expected {
+                    bail!("{project}.{service}: `{option}` is only supported by {expected}");
+                }
+                Ok(())
+            };
+
+            if manifest.replicas.is_some() {
+                only("replicas", Service::Torii)?;
+            }
+            if manifest.replication {
+                only("replication", Service::Torii)?;
+            }
+            if manifest.provable {
+                only("provable", Service::Katana)?;
+            }
+            if manifest.network.is_some() {
+                only("network", Service::Katana)?;
+            }
+            if manifest.saya {
+                only("saya", Service::Katana)?;
+            }
+            if manifest.optimistic {
+                only("optimistic", Service::Katana)?;
+            }
+            if manifest.fork_provider_url.is_some() {
+                only("fork_provider_url", Service::Katana)?;
+            }
+
+            match service {
+                Service::Katana if manifest.config.is_none() && !manifest.optimistic => {
+                    bail!("{project}.katana: either `config` or `optimistic` must be set")
+                }
+                Service::Torii if manifest.config.is_none() => {
+                    bail!("{project}.torii: `config` must be set")
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ServiceManifest {
+    /// Reads the configuration file of the service, if any.
+    pub fn read_config(&self) -> Result<Option<String>> {
+        self.config
+            .as_ref()
+            .map(|path| {
+                fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read the config {}", path.display()))
+            })
+            .transpose()
+    }
+
+    /// Returns the options of the service when it's a Katana deployment.
+    pub fn katana_options(&self) -> config::KatanaOptions {
+        config::KatanaOptions {
+            provable: self.provable,
+            network: self.network.clone(),
+            saya: self.saya,
+            optimistic: self.optimistic,
+            fork_provider_url: self.fork_provider_url.clone(),
+        }
+    }
+}
+
+/// The current state of a deployment, as returned by the API.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeploymentState {
+    /// The name of the tier, e.g. `pro`.
+    pub tier: String,
+    pub regions: Vec<String>,
+    pub version: String,
+    pub replicas: i64,
+    pub observability: bool,
+    /// The contents of the configuration file.
+    pub config: String,
+}
+
+/// What has to be done for a deployment to match the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// The deployment doesn't exist yet.
+    Create,
+    /// The deployment exists, with the given differences.
+    Update(Vec<Change>),
+    /// The deployment already matches the manifest.
+    Unchanged,
+}
+
+/// A difference between the manifest and the current state of a deployment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub field: Field,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Tier,
+    Regions,
+    Version,
+    Replicas,
+    Observability,
+    Config,
+}
+
+impl Field {
+    /// Whether the field can be changed by updating the deployment. The other fields require
+    /// re-creating it.
+    pub fn is_updatable(&self) -> bool {
+        !matches!(self, Field::Regions)
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Field::Tier => "tier",
+            Field::Regions => "regions",
+            Field::Version => "version",
+            Field::Replicas => "replicas",
+            Field::Observability => "observability",
+            Field::Config => "config",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Compares a service declared in the manifest, with the contents of its configuration file, to
+/// the current state of the deployment, if it exists.
+pub fn plan(
+    manifest: &ServiceManifest,
+    config: Option<&str>,
+    current: Option<&DeploymentState>,
+) -> Action {
+    let Some(current) = current else {
+        return Action::Create;
+    };
+
+    let mut changes = Vec::new();
+    let mut compare = |field, from: String, to: String| {
+        if from != to {
+            changes.push(Change { field, from, to });
+        }
+    };
+
+    if let Some(tier) = manifest.tier {
+        compare(Field::Tier, current.tier.clone(), tier.to_string());
+    }
+
+    if let Some(regions) = &manifest.regions {
+        let mut from = current.regions.clone();
+        let mut to = regions.clone();
+        from.sort();
+        to.sort();
+        compare(Field::Regions, from.join(", "), to.join(", "));
+    }
+
+    if let Some(version) = &manifest.version {
+        if !version::is_same_version(&current.version, version) {
+            compare(Field::Version, current.version.clone(), version.clone());
+        }
+    }
+
+    if let Some(replicas) = manifest.replicas {
+        compare(
+            Field::Replicas,
+            current.replicas.to_string(),
+            replicas.to_string(),
+        );
+    }
+
+    if let Some(observability) = manifest.observability {
+        compare(
+            Field::Observability,
+            current.observability.to_string(),
+            observability.to_string(),
+        );
+    }
+
+    if let Some(config) = config {
+        if !config::same_config(&current.config, config) {
+            compare(Field::Config, current.config.clone(), config.to_string());
+        }
+    }
+
+    if changes.is_empty() {
+        Action::Unchanged
+    } else {
+        Action::Update(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+        team = "dojo"
+
+        [deployments.game.katana]
+        tier = "pro"
+        regions = ["us-east"]
+        config = "katana.toml"
+
+        [deployments.game.torii]
+        version = "v1.6.0"
+        replicas = 2
+        config = "torii.toml"
+
+        [deployments.game-events.torii]
+        config = "events.toml"
+    "#;
+
+    fn state() -> DeploymentState {
+        DeploymentState {
+            tier: "basic".to_string(),
+            regions: vec!["us-east".to_string()],
+            version: "v1.6.0".to_string(),
+            replicas: 2,
+            observability: false,
+            config: "[indexing]\npending = true\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_manifest() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+        assert_eq!(manifest.team.as_deref(), Some("dojo"));
+
+        let services = manifest
+            .services()
+            .map(|(project, service, _)| format!("{project}.{service}"))
+            .collect::<Vec<_>>();
+        assert_eq!(services, ["game.katana", "game.torii", "game-events.torii"]);
+
+        let katana = manifest.deployments["game"].katana.as_ref().unwrap();
+        assert_eq!(katana.tier, Some(Tier::Pro));
+
+        let torii = manifest.deployments["game-events"].torii.as_ref().unwrap();
+        assert_eq!(torii.tier, None);
+        assert_eq!(torii.replicas, None);
+    }
+
+    #[test]
+    fn load_resolves_config_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(MANIFEST_FILE_NAME);
+        fs::write(&path, MANIFEST).unwrap();
+
+        let manifest = Manifest::load(&path).unwrap();
+        let katana = manifest.deployments["game"].katana.as_ref().unwrap();
+        assert_eq!(katana.config, Some(dir.path().join("katana.toml")));
+    }
+
+    #[test]
+    fn invalid_manifests() {
+        // unknown field
+        assert!(Manifest::parse("[deployments.game.torii]\nconfig = \"a\"\nreplica = 2").is_err());
+        // unknown tier
+        assert!(
+            Manifest::parse("[deployments.game.torii]\nconfig = \"a\"\ntier = \"gold\"").is_err()
+        );
+        // torii only option on katana
+        assert!(
+            Manifest::parse("[deployments.game.katana]\nconfig = \"a\"\nreplicas = 2").is_err()
+        );
+        // katana without config
+        assert!(Manifest::parse("[deployments.game.katana]\ntier = \"pro\"").is_err());
+        // optimistic katana without config
+        assert!(Manifest::parse("[deployments.game.katana]\noptimistic = true").is_ok());
+    }
+
+    #[test]
+    fn plan_create() {
+        let manifest = ServiceManifest::default();
+        assert_eq!(plan(&manifest, None, None), Action::Create);
+    }
+
+    #[test]
+    fn plan_unchanged() {
+        let manifest = ServiceManifest {
+            version: Some("1.6.0".to_string()),
+            replicas: Some(2),
+            regions: Some(vec!["us-east".to_string()]),
+            ..Default::default()
+        };
+
+        // formatting differences don't matter
+        let config = "[indexing]\npending = true # comment\n";
+        assert_eq!(
+            plan(&manifest, Some(config), Some(&state())),
+            Action::Unchanged
+        );
+    }
+
+    #[test]
+    fn plan_ignores_omitted_tier() {
+        let current = DeploymentState {
+            tier: "pro".to_string(),
+            ..state()
+        };
+
+        let manifest = ServiceManifest::default();
+        assert_eq!(plan(&manifest, None, Some(&current)), Action::Unchanged);
+
+        let manifest = ServiceManifest {
+            tier: Some(Tier::Basic),
+            ..Default::default()
+        };
+        let Action::Update(changes) = plan(&manifest, None, Some(&current)) else {
+            panic!("expected an update");
+        };
+        assert_eq!(changes[0].field, Field::Tier);
+    }
+
+    #[test]
+    fn plan_update() {
+        let manifest = ServiceManifest {
+            tier: Some(Tier::Pro),
+            version: Some("v1.7.0".to_string()),
+            replicas: Some(3),
+            regions: Some(vec!["eu-west".to_string()]),
+            observability: Some(false),
+            ..Default::default()
+        };
+
+        let config = "[indexing]\npending = false\n";
+        let Action::Update(changes) = plan(&manifest, Some(config), Some(&state())) else {
+            panic!("expected an update");
+        };
+
+        let fields = changes.iter().map(|c| c.field).collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [
+                Field::Tier,
+                Field::Regions,
+                Field::Version,
+                Field::Replicas,
+                Field::Config
+            ]
+        );
+        assert_eq!(changes[0].from, "basic");
+        assert_eq!(changes[0].to, "pro");
+        assert!(!changes[1].field.is_updatable());
+    }
+}
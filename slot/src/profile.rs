@@ -0,0 +1,343 @@
+//! Named profiles, each holding its own credentials and sessions.
+//!
+//! The `default` profile lives directly in the config directory, where the credentials and
+//! sessions were stored before profiles were introduced. Every other profile lives in its own
+//! `profiles/<name>` subdirectory.
+//!
+//! The active profile is, in order of precedence, the one selected with [`select`] (e.g. the
+//! `--profile` flag of the CLI), the `SLOT_PROFILE` environment variable, the one set with
+//! [`set_current`], or the `default` profile.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::{env, fs};
+
+use anyhow::anyhow;
+
+use crate::credential::{self};
+use crate::error::Error;
+use crate::session;
+use crate::utils::{self};
+
+/// The name of the profile used when none is selected.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The environment variable used to select the active profile.
+pub const PROFILE_ENV_VAR: &str = "SLOT_PROFILE";
+
+/// The directory, relative to the config directory, where the non-default profiles are stored.
+const PROFILES_DIR: &str = "profiles";
+/// The file, relative to the config directory, storing the name of the current profile.
+const CURRENT_PROFILE_FILE: &str = "current-profile";
+
+/// The profile selected for the lifetime of the process.
+static SELECTED_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Select the profile to use for the lifetime of the process, taking precedence over the
+/// `SLOT_PROFILE` environment variable and the current profile. Can only be called once.
+pub fn select(name: &str) -> Result<(), Error> {
+    validate_name(name)?;
+    SELECTED_PROFILE
+        .set(name.to_string())
+        .map_err(|_| anyhow!("a profile has already been selected").into())
+}
+
+/// Returns the name of the active profile.
+pub fn active() -> Result<String, Error> {
+    if let Some(name) = SELECTED_PROFILE.get() {
+        return Ok(name.clone());
+    }
+
+    if let Ok(name) = env::var(PROFILE_ENV_VAR) {
+        validate_name(&name)?;
+        return Ok(name);
+    }
+
+    current_at(utils::config_dir())
+}
+
+/// Returns the directory of the active profile. This function guarantees that the directory
+/// exists.
+pub fn active_dir() -> Result<PathBuf, Error> {
+    let name = active()?;
+    let path = dir_at(utils::config_dir(), &name)?;
+    fs::create_dir_all(&path)?;
+    Ok(path)
+}
+
+/// Returns the directory of the profile `name`.
+pub fn dir(name: &str) -> Result<PathBuf, Error> {
+    dir_at(utils::config_dir(), name)
+}
+
+/// Set the current profile, used when no profile is selected through [`select`] or the
+/// `SLOT_PROFILE` environment variable.
+pub fn set_current(name: &str) -> Result<(), Error> {
+    set_current_at(utils::config_dir(), name)
+}
+
+/// Returns the names of all the profiles with stored credentials, sorted by name.
+pub fn list() -> Result<Vec<String>, Error> {
+    list_at(utils::config_dir())
+}
+
+/// Removes the profile and everything stored in it (credentials and sessions). If it is the
+/// current profile, the current profile is reset to the default one.
+pub fn remove(name: &str) -> Result<(), Error> {
+    remove_at(utils::config_dir(), name)
+}
+
+/// Logs out of the profile, removing its credentials and sessions but keeping the profile
+/// itself. Returns `false` if the profile wasn't logged in.
+pub fn logout(name: &str) -> Result<bool, Error> {
+    logout_at(utils::config_dir(), name)
+}
+
+/// Returns the directory of the profile `name`, relative to `config_dir`.
+pub(crate) fn dir_at(config_dir: impl AsRef<Path>, name: &str) -> Result<PathBuf, Error> {
+    validate_name(name)?;
+
+    let config_dir = config_dir.as_ref();
+    if name == DEFAULT_PROFILE {
+        Ok(config_dir.to_path_buf())
+    } else {
+        Ok(config_dir.join(PROFILES_DIR).join(name))
+    }
+}
+
+fn current_at(config_dir: impl AsRef<Path>) -> Result<String, Error> {
+    let path = config_dir.as_ref().join(CURRENT_PROFILE_FILE);
+
+    if !path.exists() {
+        return Ok(DEFAULT_PROFILE.to_string());
+    }
+
+    let name = fs::read_to_string(path)?.trim().to_string();
+    validate_name(&name)?;
+    Ok(name)
+}
+
+fn set_current_at(config_dir: impl AsRef<Path>, name: &str) -> Result<(), Error> {
+    validate_name(name)?;
+    let path = config_dir.as_ref().join(CURRENT_PROFILE_FILE);
+    fs::write(path, name)?;
+    Ok(())
+}
+
+fn list_at(config_dir: impl AsRef<Path>) -> Result<Vec<String>, Error> {
+    let config_dir = config_dir.as_ref();
+    let mut profiles = Vec::new();
+
+    if credential::exists_at(config_dir) {
+        profiles.push(DEFAULT_PROFILE.to_string());
+    }
+
+    let profiles_dir = config_dir.join(PROFILES_DIR);
+    if profiles_dir.exists() {
+        for entry in fs::read_dir(profiles_dir)? {
+            let entry = entry?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+
+            if validate_name(&name).is_ok() && credential::exists_at(entry.path()) {
+                profiles.push(name);
+            }
+        }
+    }
+
+    profiles.sort();
+    Ok(profiles)
+}
+
+fn remove_at(config_dir: impl AsRef<Path>, name: &str) -> Result<(), Error> {
+    let config_dir = config_dir.as_ref();
+    let dir = dir_at(config_dir, name)?;
+
+    if name == DEFAULT_PROFILE {
+        // The default profile shares the config directory with the other profiles, so only
+        // remove the files that belong to it.
+        if !logout_at(config_dir, name)? {
+            return Err(anyhow!("profile '{name}' doesn't exist").into());
+        }
+    } else {
+        if !dir.exists() {
+            return Err(anyhow!("profile '{name}' doesn't exist").into());
+        }
+
+        // The credentials and the sessions key may be stored outside of the profile directory
+        // (e.g. in the keyring).
+        logout_at(config_dir, name)?;
+        fs::remove_dir_all(dir)?;
+    }
+
+    let current_profile_file = config_dir.join(CURRENT_PROFILE_FILE);
+    if current_profile_file.exists() && current_at(config_dir)? == name {
+        fs::remove_file(current_profile_file)?;
+    }
+
+    Ok(())
+}
+
+fn logout_at(config_dir: impl AsRef<Path>, name: &str) -> Result<bool, Error> {
+    let dir = dir_at(config_dir, name)?;
+
+    if !credential::exists_at(&dir) {
+        return Ok(false);
+    }
+
+    // The sessions are stored per account, so the credentials are needed to find them.
+    if let Ok(credentials) = credential::Credentials::load_at(&dir) {
+        session::delete_all_at(&dir, &credentials.account.id)?;
+    }
+
+    credential::delete_at(&dir)?;
+    Ok(true)
+}
+
+/// Profile names are used as directory names, so only allow a conservative set of characters.
+fn validate_name(name: &str) -> Result<(), Error> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "invalid profile name '{name}': only alphanumeric characters, '-' and '_' are allowed"
+        )
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::account::AccountInfo;
+    use crate::credential::{AccessToken, Credentials};
+
+    fn login(profile_dir: impl AsRef<Path>, username: &str) {
+        let token = AccessToken {
+            token: "mytoken".to_string(),
+            r#type: "Bearer".to_string(),
+            expires_at: None,
+            refresh_token: None,
+        };
+
+        let account = AccountInfo {
+            id: username.to_string(),
+            ..Default::default()
+        };
+
+        Credentials::store_at(profile_dir, &Credentials::new(account, token)).unwrap();
+    }
+
+    #[test]
+    fn default_profile_is_config_dir() {
+        let config_dir = utils::config_dir();
+        assert_eq!(dir_at(&config_dir, DEFAULT_PROFILE).unwrap(), config_dir);
+
+        let ci = dir_at(&config_dir, "ci").unwrap();
+        assert_eq!(ci, config_dir.join(PROFILES_DIR).join("ci"));
+    }
+
+    #[test]
+    fn invalid_profile_names() {
+        let config_dir = utils::config_dir();
+        assert!(dir_at(&config_dir, "").is_err());
+        assert!(dir_at(&config_dir, "../foo").is_err());
+        assert!(dir_at(&config_dir, "foo/bar").is_err());
+        assert!(dir_at(&config_dir, "foo bar").is_err());
+    }
+
+    #[test]
+    fn current_profile_rt() {
+        let config_dir = utils::config_dir();
+        assert_eq!(current_at(&config_dir).unwrap(), DEFAULT_PROFILE);
+
+        set_current_at(&config_dir, "ci").unwrap();
+        assert_eq!(current_at(&config_dir).unwrap(), "ci");
+    }
+
+    #[test]
+    fn list_profiles() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        env::remove_var("SLOT_AUTH");
+
+        let config_dir = utils::config_dir();
+        assert!(list_at(&config_dir).unwrap().is_empty());
+
+        login(dir_at(&config_dir, "personal").unwrap(), "foo");
+        login(dir_at(&config_dir, DEFAULT_PROFILE).unwrap(), "bar");
+        login(dir_at(&config_dir, "ci").unwrap(), "baz");
+
+        // a profile directory without credentials isn't listed
+        fs::create_dir_all(dir_at(&config_dir, "empty").unwrap()).unwrap();
+
+        let profiles = list_at(&config_dir).unwrap();
+        assert_eq!(profiles, vec!["ci", DEFAULT_PROFILE, "personal"]);
+    }
+
+    #[test]
+    fn remove_profile() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        env::remove_var("SLOT_AUTH");
+
+        let config_dir = utils::config_dir();
+        login(dir_at(&config_dir, "ci").unwrap(), "foo");
+        set_current_at(&config_dir, "ci").unwrap();
+
+        remove_at(&config_dir, "ci").unwrap();
+
+        assert!(!dir_at(&config_dir, "ci").unwrap().exists());
+        assert_eq!(current_at(&config_dir).unwrap(), DEFAULT_PROFILE);
+        assert!(remove_at(&config_dir, "ci").is_err());
+    }
+
+    #[test]
+    fn logout_keeps_profile() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        env::remove_var("SLOT_AUTH");
+
+        let config_dir = utils::config_dir();
+        let dir = dir_at(&config_dir, "ci").unwrap();
+        login(&dir, "foo");
+        set_current_at(&config_dir, "ci").unwrap();
+
+        let sessions_dir = dir.join("foo");
+        fs::create_dir_all(&sessions_dir).unwrap();
+
+        assert!(logout_at(&config_dir, "ci").unwrap());
+
+        assert!(dir.exists());
+        assert!(!sessions_dir.exists());
+        assert!(!credential::exists_at(&dir));
+        assert_eq!(current_at(&config_dir).unwrap(), "ci");
+
+        // already logged out
+        assert!(!logout_at(&config_dir, "ci").unwrap());
+    }
+
+    #[test]
+    fn remove_default_profile_keeps_other_profiles() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        env::remove_var("SLOT_AUTH");
+
+        let config_dir = utils::config_dir();
+        login(&config_dir, "foo");
+        login(dir_at(&config_dir, "ci").unwrap(), "bar");
+
+        let sessions_dir = config_dir.join("foo");
+        fs::create_dir_all(&sessions_dir).unwrap();
+
+        remove_at(&config_dir, DEFAULT_PROFILE).unwrap();
+
+        assert!(!sessions_dir.exists());
+        assert!(!credential::get_file_path(&config_dir).exists());
+        assert_eq!(list_at(&config_dir).unwrap(), vec!["ci"]);
+    }
+}
@@ -1,32 +1,95 @@
 use std::io;
-use std::net::{SocketAddr, TcpListener};
+use std::net::{IpAddr, SocketAddr, TcpListener};
+use std::sync::Arc;
+use std::time::Duration;
 
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
 use axum::Router;
+use rand::distributions::{Alphanumeric, DistString};
 use tokio::sync::mpsc::Receiver;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
+use url::Url;
+
+/// How long the callback servers wait for the browser by default.
+pub const DEFAULT_DEADLINE: Duration = Duration::from_secs(5 * 60);
+
+/// The length of the random `state` parameter.
+const STATE_LEN: usize = 32;
+
+/// Errors returned by [`LocalServer::start`] when the server stops before being shutdown.
+#[derive(Debug, thiserror::Error)]
+pub enum ServerError {
+    #[error(
+        "Timed out after {}s waiting for the browser. If the browser tab was closed, please try \
+         again.",
+        .0.as_secs()
+    )]
+    Timeout(Duration),
+}
+
+/// The address a [`LocalServer`] listens on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallbackAddr {
+    /// The host to bind to. Use `0.0.0.0` to accept the requests forwarded to a container.
+    pub host: String,
+    /// The port to bind to. A port of 0 lets the OS pick an available port.
+    pub port: u16,
+}
+
+impl Default for CallbackAddr {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 0,
+        }
+    }
+}
 
 /// A simple local server.
+///
+/// The server only accepts the requests carrying its [`state`](LocalServer::state) in the
+/// `state` query parameter, so that other pages can't forge callbacks. The URLs returned by
+/// [`callback_uri`](LocalServer::callback_uri) carry it.
 #[derive(Debug)]
 pub struct LocalServer {
     router: Router,
     listener: TcpListener,
+    host: String,
     shutdown_rx: Option<Receiver<()>>,
+    deadline: Option<Duration>,
+    state: Arc<str>,
 }
 
 impl LocalServer {
+    /// Creates a server listening on an available port of localhost.
     pub fn new(router: Router) -> anyhow::Result<Self> {
-        // Port number of 0 requests OS to find an available port.
-        let listener = TcpListener::bind("localhost:0")?;
+        Self::bind(router, &CallbackAddr::default())
+    }
+
+    /// Creates a server listening on `addr`.
+    pub fn bind(router: Router, addr: &CallbackAddr) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind((addr.host.as_str(), addr.port))?;
         listener.set_nonblocking(true)?; // !important
 
+        let state: Arc<str> = Alphanumeric
+            .sample_string(&mut rand::thread_rng(), STATE_LEN)
+            .into();
+        let router = router.layer(middleware::from_fn_with_state(state.clone(), verify_state));
+
         // To view the logs emitted by the server, set `RUST_LOG=tower_http=trace`
         let router = router.layer(TraceLayer::new_for_http());
 
         Ok(Self {
             router,
             listener,
+            host: addr.host.clone(),
             shutdown_rx: None,
+            deadline: None,
+            state,
         })
     }
 
@@ -42,10 +105,41 @@ impl LocalServer {
         self
     }
 
+    /// Stop the server with [`ServerError::Timeout`] if it hasn't been shutdown after `deadline`.
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
     pub fn local_addr(&self) -> Result<SocketAddr, io::Error> {
         self.listener.local_addr()
     }
 
+    /// The URL of `path` on the server, as reached from the browser. The URL carries the state
+    /// of the server, so that the callbacks to it are accepted.
+    ///
+    /// A server bound to all interfaces is reached through `localhost`, as when the port of a
+    /// container is published to the host.
+    pub fn callback_uri(&self, path: &str) -> anyhow::Result<Url> {
+        let port = self.local_addr()?.port();
+        let host = match self.host.parse::<IpAddr>() {
+            Ok(ip) if ip.is_unspecified() => "localhost".to_string(),
+            Ok(IpAddr::V6(ip)) => format!("[{ip}]"),
+            _ => self.host.clone(),
+        };
+
+        let mut url = Url::parse(&format!("http://{host}:{port}"))?.join(path)?;
+        url.query_pairs_mut().append_pair("state", &self.state);
+        Ok(url)
+    }
+
+    /// The random nonce that the requests must carry in their `state` query parameter.
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    /// Runs the server until it is shutdown or the deadline is reached. The server can be
+    /// cancelled by dropping the returned future.
     pub async fn start(mut self) -> anyhow::Result<()> {
         let addr = self.listener.local_addr()?;
         tracing::info!(?addr, "Callback server started");
@@ -53,22 +147,57 @@ impl LocalServer {
         let listener = tokio::net::TcpListener::from_std(self.listener)?;
         let server = axum::serve(listener, self.router.into_make_service());
 
-        if let Some(mut rx) = self.shutdown_rx.take() {
-            server
-                .with_graceful_shutdown(async move { rx.recv().await.expect("channel closed") })
-                .await?;
-        } else {
-            server.await?;
+        let serve = async move {
+            if let Some(mut rx) = self.shutdown_rx.take() {
+                server
+                    .with_graceful_shutdown(async move { rx.recv().await.expect("channel closed") })
+                    .await
+            } else {
+                server.await
+            }
+        };
+
+        let deadline = async {
+            match self.deadline {
+                Some(deadline) => tokio::time::sleep(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            result = serve => result?,
+            _ = deadline => {
+                return Err(ServerError::Timeout(self.deadline.unwrap_or_default()).into())
+            }
         }
 
         Ok(())
     }
 }
 
+/// Rejects the requests whose `state` query parameter doesn't match the server's.
+async fn verify_state(State(expected): State<Arc<str>>, request: Request, next: Next) -> Response {
+    let state = request.uri().query().and_then(|query| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == "state")
+            .map(|(_, value)| value.into_owned())
+    });
+
+    if state.as_deref() != Some(&*expected) {
+        tracing::warn!(uri = %request.uri(), "Rejected callback request with an invalid state");
+        return (StatusCode::FORBIDDEN, "Invalid state parameter").into_response();
+    }
+
+    next.run(request).await
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::server::LocalServer;
+    use std::time::Duration;
+
+    use crate::server::{CallbackAddr, LocalServer, ServerError};
     use axum::{routing::get, Router};
+    use reqwest::StatusCode;
 
     #[tokio::test]
     async fn test_server_graceful_shutdown() {
@@ -79,13 +208,14 @@ mod tests {
         let port = server.local_addr().unwrap().port();
 
         let client = reqwest::Client::new();
-        let url = format!("http://localhost:{port}/callback");
+        let url = server.callback_uri("/callback").unwrap();
+        assert_eq!(url.port(), Some(port));
 
         // start the local server
         tokio::spawn(server.start());
 
         // first request should succeed
-        assert!(client.get(&url).send().await.is_ok());
+        assert!(client.get(url.clone()).send().await.is_ok());
 
         // send shutdown signal
         tx.send(()).await.unwrap();
@@ -94,4 +224,78 @@ mod tests {
         // should've been shutdown
         assert!(client.get(url).send().await.is_err())
     }
+
+    #[tokio::test]
+    async fn test_server_rejects_invalid_state() {
+        let router = Router::new().route("/callback", get(|| async { "Hello, World!" }));
+        let server = LocalServer::new(router).unwrap();
+        let port = server.local_addr().unwrap().port();
+        let callback_uri = server.callback_uri("/callback").unwrap();
+
+        tokio::spawn(server.start());
+
+        let client = reqwest::Client::new();
+        let url = format!("http://localhost:{port}/callback");
+
+        let res = client.get(&url).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = client
+            .get(format!("{url}?state=invalid"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        // The keychain appends its own parameters to the callback URI.
+        let mut callback = callback_uri.clone();
+        callback.query_pairs_mut().append_pair("code", "1234");
+        let res = client.get(callback).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn callback_uri() {
+        let router = Router::new();
+
+        let server = LocalServer::new(router.clone()).unwrap();
+        let port = server.local_addr().unwrap().port();
+        let uri = server.callback_uri("/callback").unwrap();
+        let expected = format!("http://localhost:{port}/callback?state={}", server.state());
+        assert_eq!(uri.as_str(), expected);
+
+        let addr = CallbackAddr {
+            host: "0.0.0.0".to_string(),
+            port: 0,
+        };
+        let server = LocalServer::bind(router.clone(), &addr).unwrap();
+        let port = server.local_addr().unwrap().port();
+        let uri = server.callback_uri("/callback").unwrap();
+        let expected = format!("http://localhost:{port}/callback?state={}", server.state());
+        assert_eq!(uri.as_str(), expected);
+
+        let addr = CallbackAddr {
+            host: "127.0.0.1".to_string(),
+            port: 0,
+        };
+        let server = LocalServer::bind(router, &addr).unwrap();
+        let port = server.local_addr().unwrap().port();
+        let uri = server.callback_uri("/callback").unwrap();
+        let expected = format!("http://127.0.0.1:{port}/callback?state={}", server.state());
+        assert_eq!(uri.as_str(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_server_deadline() {
+        let router = Router::new().route("/callback", get(|| async { "Hello, World!" }));
+        let server = LocalServer::new(router)
+            .unwrap()
+            .with_deadline(Duration::from_millis(10));
+
+        let err = server.start().await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ServerError>(),
+            Some(ServerError::Timeout(_))
+        ));
+    }
 }
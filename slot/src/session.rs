@@ -1,32 +1,45 @@
 use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{fs, path::PathBuf};
 
 use account_sdk::account::session::account::SessionAccount;
 use account_sdk::account::session::hash::Session;
+use account_sdk::hash::MessageHashRev1;
 use account_sdk::provider::CartridgeJsonRpcProvider;
 use account_sdk::signers::Signer;
 use anyhow::{anyhow, Context};
 use axum::response::{IntoResponse, Response};
 use axum::{extract::State, routing::post, Router};
 use cainome_cairo_serde::NonZero;
+use dialoguer::Input;
 use hyper::StatusCode;
 use serde::{Deserialize, Serialize};
-use starknet::core::types::Felt;
-use starknet::core::utils::{get_selector_from_name, NonAsciiNameError};
+use starknet::accounts::Account;
+use starknet::core::types::{Call, Felt};
+use starknet::core::utils::{get_selector_from_name, parse_cairo_short_string, NonAsciiNameError};
 use starknet::macros::short_string;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, Provider};
 use starknet::signers::SigningKey;
+use starknet_crypto::poseidon_hash;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tower_http::cors::CorsLayer;
 use tracing::info;
 use url::Url;
 
-use crate::credential::Credentials;
+use crate::api::Client;
+use crate::credential::{Credentials, CredentialsStore};
 use crate::error::Error;
-use crate::utils::{self};
-use crate::{browser, server::LocalServer, vars};
-use account_sdk::account::session::policy::{CallPolicy, Policy};
+use crate::graphql::session::{revoke_sessions, RevokeSessions};
+use crate::graphql::GraphQLQuery;
+use crate::preset::ControllerConfig;
+use crate::server::{self, CallbackAddr, LocalServer};
+use crate::{browser, profile, vars};
+use account_sdk::account::session::policy::{
+    CallPolicy, Policy, TypedDataPolicy as AccountTypedDataPolicy,
+};
+
+mod storage;
 
 // Taken from: https://github.com/cartridge-gg/controller/blob/1d7352fce437ccd0b992ca5420aeb3719427e348/packages/account-wasm/src/lib.rs#L92-L95
 const GUARDIAN: Felt = short_string!("CARTRIDGE_GUARDIAN");
@@ -55,10 +68,45 @@ pub struct FullSessionInfo {
     pub chain_id: Felt,
     pub auth: SessionAuth,
     pub session: Session,
+    /// The policies the session was requested with. Empty for sessions created before they were
+    /// recorded.
+    #[serde(default)]
+    pub policies: Vec<SessionPolicy>,
+    /// The RPC URL of the network the session was created for.
+    #[serde(default)]
+    pub rpc_url: Option<Url>,
+    /// The timestamp, in seconds, at which the session expires.
+    #[serde(default)]
+    pub expires_at: u64,
+    /// The authorization of the session by the owner of the account, identifying it in the API.
+    /// Empty when the keychain didn't return it.
+    #[serde(default)]
+    pub authorization: Vec<Felt>,
 }
 
 impl FullSessionInfo {
-    /// Convert the session info into a [`SessionAccount`] instance.
+    /// Returns the hash identifying the session on-chain, used to revoke it.
+    pub fn hash(&self) -> Felt {
+        self.session
+            .inner
+            .get_message_hash_rev_1(self.chain_id, self.auth.address)
+    }
+
+    /// Returns the name of the session's chain id as known by the API (e.g. `SN_MAIN`).
+    pub fn chain_name(&self) -> String {
+        chain_id_name(self.chain_id)
+    }
+
+    /// Returns whether the session has expired.
+    pub fn is_expired(&self) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        self.expires_at <= now.as_secs()
+    }
+
+    /// Convert the session info into a [`SessionAccount`] instance. The account can execute the
+    /// calls and sign the typed data messages allowed by the session's policies.
     pub fn into_account(self, provider: CartridgeJsonRpcProvider) -> SessionAccount {
         let session_signer = Signer::Starknet(SigningKey::from_secret_scalar(self.auth.signer));
 
@@ -74,6 +122,16 @@ impl FullSessionInfo {
 }
 
 /// A policy defines what action can be performed by the session key.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum SessionPolicy {
+    /// Allows the session key to call a contract method.
+    Call(PolicyMethod),
+    /// Allows the session key to sign off-chain typed data messages (SNIP-12).
+    TypedData(TypedDataPolicy),
+}
+
+/// A policy that allows the session key to call a contract method.
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PolicyMethod {
     /// The target contract address.
@@ -82,25 +140,309 @@ pub struct PolicyMethod {
     pub method: String,
 }
 
-/// Retrieves the session for the given chain id of the currently authenticated user.
-/// Returns `None` if no session can be found for the chain id.
+/// A policy that allows the session key to sign the SNIP-12 typed data messages of a given type,
+/// for a given domain.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TypedDataPolicy {
+    /// The hash of the typed data domain.
+    pub domain_hash: Felt,
+    /// The hash of the primary type of the messages.
+    pub type_hash: Felt,
+}
+
+impl TypedDataPolicy {
+    /// Returns the scope hash identifying the policy in the session, i.e. the Poseidon hash of the
+    /// domain hash and the type hash.
+    pub fn scope_hash(&self) -> Felt {
+        poseidon_hash(self.domain_hash, self.type_hash)
+    }
+}
+
+impl From<PolicyMethod> for SessionPolicy {
+    fn from(value: PolicyMethod) -> Self {
+        Self::Call(value)
+    }
+}
+
+impl From<TypedDataPolicy> for SessionPolicy {
+    fn from(value: TypedDataPolicy) -> Self {
+        Self::TypedData(value)
+    }
+}
+
+/// Options for creating a session with [`create_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct SessionOptions {
+    /// The key that will be authorized to use the session. A random key is generated if `None`.
+    pub signer: Option<SigningKey>,
+    /// The maximum lifetime of the session. The keychain decides of the lifetime if `None`.
+    pub max_lifetime: Option<Duration>,
+    /// The address of the local server receiving the session from the browser.
+    pub callback: CallbackAddr,
+    /// Prompt the user to paste the session shown by the keychain instead of receiving it on a
+    /// local server, for machines that can't receive inbound HTTP requests.
+    pub paste: bool,
+}
+
+/// Retrieves the session for the given chain id of the currently authenticated user of the
+/// active profile. Returns `None` if no session can be found for the chain id.
 ///
 /// # Errors
 ///
-/// This function will return an error if there is no authenticated user.
+/// This function will return an error if there is no authenticated user, or
+/// [`Error::SessionExpired`] if the session has expired. Use [`list`] to retrieve expired
+/// sessions.
 ///
 pub fn get(chain: Felt) -> Result<Option<FullSessionInfo>, Error> {
-    get_at(utils::config_dir(), chain)
+    get_at(profile::active_dir()?, chain)
 }
 
-/// Stores the session on-disk. Returns the path to the file where the `session` has been written to.
+/// Stores the session on-disk, in the active profile. Returns the path to the file where the
+/// `session` has been written to.
 ///
 /// # Errors
 ///
 /// This function will return an error if there is no authenticated user.
 ///
 pub fn store(chain: Felt, session: &FullSessionInfo) -> Result<PathBuf, Error> {
-    store_at(utils::config_dir(), chain, session)
+    store_at(profile::active_dir()?, chain, session)
+}
+
+/// Returns all the sessions stored on-disk for the currently authenticated user of the active
+/// profile.
+///
+/// # Errors
+///
+/// This function will return an error if there is no authenticated user.
+///
+pub fn list() -> Result<Vec<FullSessionInfo>, Error> {
+    list_at(profile::active_dir()?)
+}
+
+/// Removes the session of the chain id `chain` for the currently authenticated user of the
+/// active profile. Returns `false` if there was no session for the chain id.
+///
+/// # Errors
+///
+/// This function will return an error if there is no authenticated user.
+///
+pub fn delete(chain: Felt) -> Result<bool, Error> {
+    delete_at(profile::active_dir()?, chain)
+}
+
+/// Creates a new session with the same policies, and on the same network, as `session`. Use
+/// `rpc_url` to override the network's RPC URL the session was created with.
+///
+/// # Errors
+///
+/// This function will return an error if the policies of the session weren't recorded, as is the
+/// case for sessions created with older versions.
+///
+pub async fn renew(
+    session: &FullSessionInfo,
+    rpc_url: Option<Url>,
+) -> Result<FullSessionInfo, Error> {
+    if session.policies.is_empty() {
+        return Err(anyhow!(
+            "the policies of the session weren't recorded, create a new session instead"
+        )
+        .into());
+    }
+
+    let rpc_url = rpc_url
+        .or_else(|| session.rpc_url.clone())
+        .ok_or_else(|| anyhow!("the session has no recorded RPC URL, specify one"))?;
+
+    create_with_policies(rpc_url, &session.policies).await
+}
+
+/// Encodes the session so that it can be moved to another machine with [`import`]. The session
+/// is encrypted with a passphrase, read from the `SLOT_PASSPHRASE` environment variable or
+/// prompted to the user.
+pub fn export(session: &FullSessionInfo) -> Result<String, Error> {
+    storage::export(session)
+}
+
+/// Decodes a session encoded with [`export`]. The decoded session can then be stored with
+/// [`store`].
+pub fn import(contents: &str) -> Result<FullSessionInfo, Error> {
+    storage::import(contents)
+}
+
+/// Returns all the sessions stored on-disk for the authenticated user of the profile `name`.
+pub fn list_profile(name: &str) -> Result<Vec<FullSessionInfo>, Error> {
+    list_at(profile::dir(name)?)
+}
+
+/// Revokes the `sessions` server-side, so that they can't be used anymore even by someone who
+/// has a copy of the session files.
+pub async fn revoke(client: &Client, sessions: &[FullSessionInfo]) -> Result<(), Error> {
+    if sessions.is_empty() {
+        return Ok(());
+    }
+
+    let sessions = sessions
+        .iter()
+        .map(|session| revoke_sessions::RevokeSessionInput {
+            session_hash: session.hash(),
+            username: session.auth.username.clone(),
+            chain_id: chain_id_name(session.chain_id),
+        })
+        .collect();
+
+    let request_body = RevokeSessions::build_query(revoke_sessions::Variables { sessions });
+    let res: revoke_sessions::ResponseData = client.query(&request_body).await?;
+
+    if res.revoke_sessions {
+        Ok(())
+    } else {
+        Err(anyhow!("the sessions could not be revoked").into())
+    }
+}
+
+/// Executes `calls` in a single transaction, through the stored session of the chain id `chain`
+/// of the currently authenticated user. Returns the hash of the submitted transaction.
+///
+/// The transaction is sent to the RPC URL the session was created with. Its fee is estimated
+/// before it is signed with the session key and submitted.
+///
+/// # Errors
+///
+/// This function will return an error if there is no valid session for the chain id, or if
+/// any of the calls is not allowed by the session's policies.
+///
+pub async fn execute(chain: Felt, calls: Vec<Call>) -> Result<Felt, Error> {
+    let session = get(chain)?
+        .ok_or_else(|| anyhow!("no session found for chain {}", chain_id_name(chain)))?;
+
+    ensure_calls_allowed(&session, &calls)?;
+
+    let rpc_url = session.rpc_url.clone().ok_or_else(|| {
+        anyhow!("the session has no recorded RPC URL, renew it with `slot sessions renew`")
+    })?;
+
+    let provider = CartridgeJsonRpcProvider::new(rpc_url);
+    let account = session.into_account(provider);
+
+    let execution = account.execute_v3(calls);
+    execution
+        .estimate_fee()
+        .await
+        .map_err(|e| anyhow!("failed to estimate the transaction fee: {e}"))?;
+
+    let result = execution
+        .send()
+        .await
+        .map_err(|e| anyhow!("failed to send the transaction: {e}"))?;
+
+    Ok(result.transaction_hash)
+}
+
+/// Why a call is, or isn't, covered by the policies of a session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallCoverage {
+    /// The call is allowed by the call policy of the session for its method.
+    Allowed(PolicyMethod),
+    /// The session has no policy for the contract being called.
+    UnknownContract,
+    /// The session has policies for the contract, but none for the method being called.
+    UnknownMethod,
+    /// The policies of the session weren't recorded when it was created, so the call can't be
+    /// checked offline.
+    UnknownPolicies,
+}
+
+impl CallCoverage {
+    /// Returns whether the call is allowed by the session.
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allowed(_))
+    }
+}
+
+impl std::fmt::Display for CallCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allowed(method) => write!(f, "allowed by the policy for `{}`", method.method),
+            Self::UnknownContract => write!(f, "no policy for the contract"),
+            Self::UnknownMethod => write!(f, "no policy for the method of the contract"),
+            Self::UnknownPolicies => write!(f, "the policies of the session weren't recorded"),
+        }
+    }
+}
+
+/// The coverage of a call by the policies of a session, as returned by [`check_calls`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallCheck {
+    /// The checked call.
+    pub call: Call,
+    /// Whether the call is covered by the session, and why.
+    pub coverage: CallCoverage,
+}
+
+/// Reports, for each of the `calls`, whether it is allowed by the call policies of the session.
+///
+/// This is done offline: a call is covered if the session has a call policy for the called
+/// contract whose method name hashes to the selector of the call. Addresses are compared as field
+/// elements, so that e.g. leading zeros don't matter.
+pub fn check_calls(session: &FullSessionInfo, calls: &[Call]) -> Vec<CallCheck> {
+    // Policies with a method name that isn't a valid selector can't match any call.
+    let methods = session
+        .policies
+        .iter()
+        .filter_map(|policy| match policy {
+            SessionPolicy::Call(method) => get_selector_from_name(&method.method)
+                .ok()
+                .map(|selector| (method, selector)),
+            SessionPolicy::TypedData(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    calls
+        .iter()
+        .map(|call| {
+            let coverage = if session.policies.is_empty() {
+                CallCoverage::UnknownPolicies
+            } else if let Some((method, _)) = methods
+                .iter()
+                .find(|(method, selector)| method.target == call.to && *selector == call.selector)
+            {
+                CallCoverage::Allowed((*method).clone())
+            } else if methods.iter().any(|(method, _)| method.target == call.to) {
+                CallCoverage::UnknownMethod
+            } else {
+                CallCoverage::UnknownContract
+            };
+
+            CallCheck {
+                call: call.clone(),
+                coverage,
+            }
+        })
+        .collect()
+}
+
+/// Checks that all the `calls` are allowed by the call policies of the session.
+fn ensure_calls_allowed(session: &FullSessionInfo, calls: &[Call]) -> Result<(), Error> {
+    let denied = check_calls(session, calls)
+        .into_iter()
+        .find(|check| !check.coverage.is_allowed());
+
+    match denied {
+        None => Ok(()),
+        Some(CallCheck {
+            coverage: CallCoverage::UnknownPolicies,
+            ..
+        }) => Err(anyhow!(
+            "the policies of the session weren't recorded, renew it with `slot sessions renew`"
+        )
+        .into()),
+        Some(CallCheck { call, coverage }) => Err(anyhow!(
+            "the call to selector {:#x} of contract {:#x} is not allowed by the session: {coverage}",
+            call.selector,
+            call.to
+        )
+        .into()),
+    }
 }
 
 /// Creates a new session token for the given set of parameters for the currently authenticated user.
@@ -116,13 +458,75 @@ pub fn store(chain: Felt, session: &FullSessionInfo) -> Result<PathBuf, Error> {
 /// This function will return an error if there is no authenticated user.
 ///
 pub async fn create(rpc_url: Url, policies: &[PolicyMethod]) -> Result<FullSessionInfo, Error> {
-    // TODO: allow user configurable.
-    let signer = SigningKey::from_random();
+    let policies = policies
+        .iter()
+        .cloned()
+        .map(SessionPolicy::from)
+        .collect::<Vec<_>>();
+
+    create_with_policies(rpc_url, &policies).await
+}
+
+/// Creates a new session token like [`create`], with policies that may also allow the session to
+/// sign typed data messages.
+///
+/// # Errors
+///
+/// This function will return an error if there is no authenticated user.
+///
+pub async fn create_with_policies(
+    rpc_url: Url,
+    policies: &[SessionPolicy],
+) -> Result<FullSessionInfo, Error> {
+    create_with_options(rpc_url, policies, &SessionOptions::default()).await
+}
+
+/// Creates a new session token like [`create_with_policies`], with the signer and the maximum
+/// lifetime of the session configured by `options`.
+///
+/// # Errors
+///
+/// This function will return an error if there is no authenticated user, or if the approved
+/// session outlives the requested maximum lifetime.
+///
+pub async fn create_with_options(
+    rpc_url: Url,
+    policies: &[SessionPolicy],
+    options: &SessionOptions,
+) -> Result<FullSessionInfo, Error> {
+    let chain_id = get_network_chain_id(rpc_url.clone()).await?;
+    create_on_chain(rpc_url, chain_id, policies, options).await
+}
+
+/// Creates a new session on the chain `chain_id` of the network of `rpc_url`.
+async fn create_on_chain(
+    rpc_url: Url,
+    chain_id: Felt,
+    policies: &[SessionPolicy],
+    options: &SessionOptions,
+) -> Result<FullSessionInfo, Error> {
+    let signer = options
+        .signer
+        .clone()
+        .unwrap_or_else(SigningKey::from_random);
     let pubkey = signer.verifying_key().scalar();
 
+    // Validated before the user is asked to approve the session.
+    let max_expires_at = options
+        .max_lifetime
+        .map(|lifetime| max_expires_at(lifetime, SystemTime::now()))
+        .transpose()?;
+
     let credentials = Credentials::load()?;
     let username = credentials.account.id;
-    let response = create_user_session(pubkey, &username, rpc_url.clone(), policies).await?;
+    let input = SessionCreationInput {
+        policies,
+        username: &username,
+        public_key: pubkey,
+        rpc_url: rpc_url.as_str(),
+        max_expires_at,
+    };
+    let response = request_user_session(input, options).await?;
 
     let auth = SessionAuth {
         address: response.address,
@@ -133,20 +537,20 @@ pub async fn create(rpc_url: Url, policies: &[PolicyMethod]) -> Result<FullSessi
 
     let methods = policies
         .iter()
-        .map(|p| {
-            let selector = get_selector_from_name(&p.method)?;
-            Ok(account_sdk::account::session::policy::Policy::Call(
-                CallPolicy {
-                    contract_address: p.target,
-                    selector,
-                    authorized: Some(true),
-                },
-            ))
-        })
+        .map(Policy::try_from)
         .collect::<Result<Vec<_>, _>>()
         .map_err(Error::InvalidMethodName)?;
 
     let expires_at = response.expires_at.parse::<u64>().map_err(|e| anyhow!(e))?;
+    if let Some(max_expires_at) = max_expires_at {
+        if expires_at > max_expires_at {
+            return Err(anyhow!(
+                "the approved session expires at {expires_at}, later than the requested {max_expires_at}"
+            )
+            .into());
+        }
+    }
+
     let session = Session::new(
         methods,
         expires_at,
@@ -158,12 +562,94 @@ pub async fn create(rpc_url: Url, policies: &[PolicyMethod]) -> Result<FullSessi
         ),
         Felt::ZERO,
     )?;
-    let chain_id = get_network_chain_id(rpc_url).await?;
 
     Ok(FullSessionInfo {
         auth,
         session,
         chain_id,
+        policies: policies.to_vec(),
+        rpc_url: Some(rpc_url),
+        expires_at,
+        authorization: response.authorization,
+    })
+}
+
+/// Creates a new session for the currently authenticated user, with the policies of the preset
+/// for the chain of the network of `rpc_url`. Set `required_only` to only request the methods
+/// that the preset marks as required. See [`create_with_options`] for the `options`.
+///
+/// # Errors
+///
+/// This function will return an error if there is no authenticated user, or if the preset has
+/// no policies for the chain.
+///
+pub async fn create_from_preset(
+    rpc_url: Url,
+    preset: &ControllerConfig,
+    required_only: bool,
+    options: &SessionOptions,
+) -> Result<FullSessionInfo, Error> {
+    let chain_id = get_network_chain_id(rpc_url.clone()).await?;
+
+    let policies = preset_policies(preset, chain_id, required_only)?;
+    let policies = policies
+        .into_iter()
+        .map(SessionPolicy::from)
+        .collect::<Vec<_>>();
+
+    create_on_chain(rpc_url, chain_id, &policies, options).await
+}
+
+/// Returns the session policies of the chain `chain` from the preset's configuration, sorted by
+/// contract address.
+///
+/// Required methods must be valid policies, while invalid optional ones are skipped so that a
+/// single malformed entry doesn't prevent creating a session.
+pub fn preset_policies(
+    preset: &ControllerConfig,
+    chain: Felt,
+    required_only: bool,
+) -> Result<Vec<PolicyMethod>, Error> {
+    let chain_name = chain_id_name(chain);
+    let chain_policies = preset
+        .chains
+        .get(&chain_name)
+        .ok_or_else(|| anyhow!("the preset has no policies for chain {chain_name}"))?;
+
+    let mut policies = Vec::new();
+
+    for (address, contract) in &chain_policies.policies.contracts {
+        for method in &contract.methods {
+            if required_only && !method.is_required {
+                continue;
+            }
+
+            match preset_policy(address, &method.entrypoint) {
+                Ok(policy) => policies.push(policy),
+                Err(err) if method.is_required => return Err(err),
+                Err(err) => {
+                    tracing::warn!(%address, entrypoint = %method.entrypoint, %err, "Skipping invalid optional policy.");
+                }
+            }
+        }
+    }
+
+    if policies.is_empty() {
+        return Err(anyhow!("the preset has no session policies for chain {chain_name}").into());
+    }
+
+    policies.sort_by(|a, b| (a.target, &a.method).cmp(&(b.target, &b.method)));
+    Ok(policies)
+}
+
+fn preset_policy(address: &str, entrypoint: &str) -> Result<PolicyMethod, Error> {
+    let target = Felt::from_hex(address)
+        .map_err(|e| anyhow!("invalid contract address '{address}': {e}"))?;
+    get_selector_from_name(entrypoint).map_err(Error::InvalidMethodName)?;
+
+    Ok(PolicyMethod {
+        target,
+        method: entrypoint.to_string(),
     })
 }
 
@@ -176,13 +662,29 @@ fn get_at(config_dir: impl AsRef<Path>, chain: Felt) -> Result<Option<FullSessio
     let user_path = get_user_relative_file_path(&username, chain);
     let file_path = config_dir.as_ref().join(user_path);
 
-    if file_path.exists() {
-        let contents = fs::read_to_string(file_path)?;
-        let session = serde_json::from_str(&contents)?;
-        Ok(Some(session))
-    } else {
-        Ok(None)
+    if !file_path.exists() {
+        return Ok(None);
     }
+
+    let session = read_session(&config_dir, &file_path)?;
+    if session.is_expired() {
+        return Err(Error::SessionExpired {
+            expires_at: session.expires_at,
+        });
+    }
+
+    Ok(Some(session))
+}
+
+fn read_session(config_dir: impl AsRef<Path>, path: &Path) -> Result<FullSessionInfo, Error> {
+    let mut session = storage::read(config_dir.as_ref(), path)?;
+
+    // Sessions created before the expiry was recorded.
+    if session.expires_at == 0 {
+        session.expires_at = session.session.inner.expires_at;
+    }
+
+    Ok(session)
 }
 
 /// Stores the session token of the chain id `chain` for the currently authenticated user. It will
@@ -207,12 +709,71 @@ fn store_at(
         }
     }
 
-    let contents = serde_json::to_string_pretty(session)?;
-    fs::write(&file_path, contents)?;
+    let store = CredentialsStore::from_env()?;
+    storage::write(config_dir.as_ref(), &file_path, session, store)?;
 
     Ok(file_path)
 }
 
+/// Removes the session of the chain id `chain` for the currently authenticated user. It will use
+/// `config_dir` as the root path to look for the session file.
+fn delete_at(config_dir: impl AsRef<Path>, chain: Felt) -> Result<bool, Error> {
+    let credentials = Credentials::load_at(&config_dir)?;
+    let username = credentials.account.id;
+
+    let file_path = config_dir
+        .as_ref()
+        .join(get_user_relative_file_path(&username, chain));
+
+    if file_path.exists() {
+        fs::remove_file(file_path)?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Returns all the sessions of the currently authenticated user, stored under `config_dir`.
+fn list_at(config_dir: impl AsRef<Path>) -> Result<Vec<FullSessionInfo>, Error> {
+    let credentials = Credentials::load_at(&config_dir)?;
+    let sessions_dir = config_dir.as_ref().join(credentials.account.id);
+
+    if !sessions_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(sessions_dir)? {
+        let path = entry?.path();
+        let is_session_file = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(SESSION_FILE_BASE_NAME));
+
+        if is_session_file {
+            sessions.push(read_session(&config_dir, &path)?);
+        }
+    }
+
+    Ok(sessions)
+}
+
+/// Removes all the sessions of the account `account_id`, stored under `config_dir`.
+pub(crate) fn delete_all_at(config_dir: impl AsRef<Path>, account_id: &str) -> Result<(), Error> {
+    let sessions_dir = config_dir.as_ref().join(account_id);
+    if sessions_dir.exists() {
+        fs::remove_dir_all(sessions_dir)?;
+    }
+
+    storage::delete_key(config_dir.as_ref())
+}
+
+/// Returns the name of the chain id (e.g. `SN_MAIN`) as expected by the API, or its hex
+/// representation if it's not a short string.
+fn chain_id_name(chain_id: Felt) -> String {
+    parse_cairo_short_string(&chain_id).unwrap_or_else(|_| format!("{chain_id:#x}"))
+}
+
 /// The response object to the session creation request.
 //
 // A reflection of https://github.com/cartridge-gg/controller/blob/1ac2995e4d430e9d3b88e3a62f4d3eb21a2496c3/packages/keychain/src/pages/session.tsx#L15-L22
@@ -237,6 +798,9 @@ pub struct SessionCreationResponse {
     /// before.
     #[serde(default)]
     pub already_registered: bool,
+    /// The authorization of the session by the owner of the account. Empty if not returned.
+    #[serde(default)]
+    pub authorization: Vec<Felt>,
 }
 
 impl SessionCreationResponse {
@@ -279,53 +843,124 @@ pub async fn create_user_session(
     policies: &[PolicyMethod],
 ) -> Result<SessionCreationResponse, Error> {
     let rpc_url: Url = rpc_url.into();
+    let policies = policies
+        .iter()
+        .cloned()
+        .map(SessionPolicy::from)
+        .collect::<Vec<_>>();
+
     let input = SessionCreationInput {
-        policies,
+        policies: &policies,
         username,
         public_key,
         rpc_url: rpc_url.as_str(),
+        max_expires_at: None,
+    };
+
+    request_user_session(input, &SessionOptions::default()).await
+}
+
+/// Requests a new session like [`create_user_session`], receiving it as configured by `options`.
+async fn request_user_session(
+    input: SessionCreationInput<'_>,
+    options: &SessionOptions,
+) -> Result<SessionCreationResponse, Error> {
+    let encoded_response = if options.paste {
+        paste_session_response(input)?
+    } else {
+        let (server, mut rx) = open_session_creation_page(input, &options.callback)?;
+        server.start().await?;
+        rx.recv().await.context("Failed to received the session.")?
     };
 
-    let mut rx = open_session_creation_page(input)?;
-    let encoded_response = rx.recv().await.context("Failed to received the session.")?;
     let response = SessionCreationResponse::from_encoded(&encoded_response)?;
 
     Ok(response)
 }
 
+/// The shortest maximum lifetime that can be requested for a session.
+const MIN_SESSION_LIFETIME: Duration = Duration::from_secs(60);
+
+/// Returns the latest expiration timestamp, in seconds, of a session created at `now` that must
+/// not outlive `max_lifetime`.
+fn max_expires_at(max_lifetime: Duration, now: SystemTime) -> Result<u64, Error> {
+    if max_lifetime < MIN_SESSION_LIFETIME {
+        return Err(anyhow!(
+            "the maximum lifetime of a session must be at least {} seconds",
+            MIN_SESSION_LIFETIME.as_secs()
+        )
+        .into());
+    }
+
+    let expires_at = now
+        .checked_add(max_lifetime)
+        .and_then(|expires_at| expires_at.duration_since(UNIX_EPOCH).ok())
+        .ok_or_else(|| anyhow!("the maximum lifetime of the session is too long"))?;
+
+    Ok(expires_at.as_secs())
+}
+
 /// Input parameters for creating a new session.
 struct SessionCreationInput<'a> {
     public_key: Felt,
     username: &'a str,
     rpc_url: &'a str,
-    policies: &'a [PolicyMethod],
+    policies: &'a [SessionPolicy],
+    max_expires_at: Option<u64>,
 }
 
 /// Starts the session creation process by opening the browser to the Cartridge keychain to prompt
-/// the user to approve the session creation.
+/// the user to approve the session creation. The returned server must be started to receive the
+/// session, which is then sent to the returned receiver.
 fn open_session_creation_page(
     input: SessionCreationInput<'_>,
-) -> anyhow::Result<Receiver<EncodedResponse>> {
-    let params = prepare_query_params(input)?;
-    let host = vars::get_cartridge_keychain_url();
-    let url = format!("{host}{SESSION_CREATION_PATH}?{params}");
-
+    callback: &CallbackAddr,
+) -> anyhow::Result<(LocalServer, Receiver<EncodedResponse>)> {
     let (tx, rx) = mpsc::channel(1);
-    let server = callback_server(tx)?;
+    let server = callback_server(tx, callback)?;
+
+    let url = session_creation_callback_url(input, &server)?;
+    browser::open(url.as_str())?;
 
-    // get the callback server url
-    let port = server.local_addr()?.port();
-    let mut url = Url::parse(&url)?;
+    Ok((server, rx))
+}
 
-    // append the callback uri to the query params
-    let callback_uri = format!("http://localhost:{port}/callback");
+/// The URL of the keychain page approving the creation of the session, which sends the approved
+/// session to the callback `server`.
+fn session_creation_callback_url(
+    input: SessionCreationInput<'_>,
+    server: &LocalServer,
+) -> anyhow::Result<Url> {
+    // The callback uri carries the state of the server.
+    let callback_uri = server.callback_uri("/callback")?;
+
+    let mut url = session_creation_url(input)?;
     url.query_pairs_mut()
-        .append_pair("callback_uri", &callback_uri);
+        .append_pair("callback_uri", callback_uri.as_str());
+
+    Ok(url)
+}
 
+/// Opens the browser to the Cartridge keychain without a callback URI, so that the keychain shows
+/// the encoded session once approved, and prompts the user to paste it.
+fn paste_session_response(input: SessionCreationInput<'_>) -> anyhow::Result<EncodedResponse> {
+    let url = session_creation_url(input)?;
     browser::open(url.as_str())?;
-    tokio::spawn(server.start());
 
-    Ok(rx)
+    let encoded: String = Input::new()
+        .with_prompt("Paste the session shown by the keychain once approved")
+        .interact_text()?;
+
+    Ok(encoded.trim().to_string())
+}
+
+/// The URL of the keychain page approving the creation of the session.
+fn session_creation_url(input: SessionCreationInput<'_>) -> anyhow::Result<Url> {
+    let params = prepare_query_params(input)?;
+    let host = vars::get_cartridge_keychain_url();
+    Ok(Url::parse(&format!(
+        "{host}{SESSION_CREATION_PATH}?{params}"
+    ))?)
 }
 
 fn prepare_query_params(input: SessionCreationInput<'_>) -> Result<String, serde_json::Error> {
@@ -337,10 +972,16 @@ fn prepare_query_params(input: SessionCreationInput<'_>) -> Result<String, serde
         .collect::<Result<Vec<String>, _>>()?
         .join(",");
 
-    Ok(format!(
+    let mut params = format!(
         "username={}&public_key={}&rpc_url={}&policies=[{}]",
         input.username, input.public_key, input.rpc_url, policies
-    ))
+    );
+
+    if let Some(expires_at) = input.max_expires_at {
+        params.push_str(&format!("&expires_at={expires_at}"));
+    }
+
+    Ok(params)
 }
 
 // Base64 encoded response sent from the internal server.
@@ -364,7 +1005,10 @@ impl IntoResponse for CallbackError {
 }
 
 /// Create the callback server that will receive the session token from the browser.
-fn callback_server(result_sender: Sender<EncodedResponse>) -> anyhow::Result<LocalServer> {
+fn callback_server(
+    result_sender: Sender<EncodedResponse>,
+    addr: &CallbackAddr,
+) -> anyhow::Result<LocalServer> {
     type HandlerState = State<(Sender<EncodedResponse>, Sender<()>)>;
 
     // Request handler for the /callback endpoint.
@@ -395,9 +1039,10 @@ fn callback_server(result_sender: Sender<EncodedResponse>) -> anyhow::Result<Loc
         .route("/callback", post(handler))
         .with_state((result_sender, shutdown_tx));
 
-    Ok(LocalServer::new(router)?
+    Ok(LocalServer::bind(router, addr)?
         .cors(CorsLayer::permissive())
-        .with_shutdown_signal(shutdown_rx))
+        .with_shutdown_signal(shutdown_rx)
+        .with_deadline(server::DEFAULT_DEADLINE))
 }
 
 fn get_user_relative_file_path(username: &str, chain_id: Felt) -> PathBuf {
@@ -432,6 +1077,31 @@ impl TryFrom<&PolicyMethod> for Policy {
     }
 }
 
+impl From<&TypedDataPolicy> for Policy {
+    fn from(value: &TypedDataPolicy) -> Self {
+        Policy::TypedData(AccountTypedDataPolicy {
+            scope_hash: value.scope_hash(),
+            authorized: Some(true),
+        })
+    }
+}
+
+/// Converts the policy into an authorized session policy.
+impl TryFrom<&SessionPolicy> for Policy {
+    type Error = NonAsciiNameError;
+
+    fn try_from(value: &SessionPolicy) -> Result<Self, Self::Error> {
+        match value {
+            SessionPolicy::Call(method) => Ok(Policy::Call(CallPolicy {
+                contract_address: method.target,
+                selector: get_selector_from_name(&method.method)?,
+                authorized: Some(true),
+            })),
+            SessionPolicy::TypedData(typed_data) => Ok(Policy::from(typed_data)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -453,6 +1123,8 @@ mod tests {
         let token = AccessToken {
             token: "mytoken".to_string(),
             r#type: "Bearer".to_string(),
+            expires_at: None,
+            refresh_token: None,
         };
 
         let account = AccountInfo {
@@ -521,7 +1193,11 @@ mod tests {
         let expected = FullSessionInfo {
             chain_id: chain,
             auth: SessionAuth::default(),
-            session: Session::new_wildcard(0, &session_signer, Felt::ZERO).unwrap(),
+            session: Session::new_wildcard(u64::MAX, &session_signer, Felt::ZERO).unwrap(),
+            policies: Vec::new(),
+            rpc_url: None,
+            expires_at: u64::MAX,
+            authorization: Vec::new(),
         };
         let path = store_at(&config_dir, chain, &expected).unwrap();
 
@@ -532,6 +1208,289 @@ mod tests {
         assert!(path.ends_with(user_path));
     }
 
+    #[test]
+    fn get_expired_session() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        std::env::remove_var("SLOT_AUTH");
+
+        let config_dir = utils::config_dir();
+        authenticate(&config_dir);
+
+        let chain = felt!("0x999");
+        let signer_key = SigningKey::from_random();
+        let session_signer = account_sdk::abigen::controller::Signer::Starknet(StarknetSigner {
+            pubkey: NonZero::new(signer_key.verifying_key().scalar()).unwrap(),
+        });
+        let session = FullSessionInfo {
+            chain_id: chain,
+            auth: SessionAuth::default(),
+            session: Session::new_wildcard(1, &session_signer, Felt::ZERO).unwrap(),
+            policies: Vec::new(),
+            rpc_url: None,
+            // the expiry of sessions stored before it was recorded is taken from the session
+            expires_at: 0,
+            authorization: Vec::new(),
+        };
+        store_at(&config_dir, chain, &session).unwrap();
+
+        let err = get_at(&config_dir, chain).unwrap_err();
+        assert!(matches!(err, Error::SessionExpired { expires_at: 1 }));
+
+        // expired sessions are still listed
+        let sessions = list_at(&config_dir).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].expires_at, 1);
+    }
+
+    #[test]
+    fn list_and_delete_sessions() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        std::env::remove_var("SLOT_AUTH");
+
+        let config_dir = utils::config_dir();
+        let username = authenticate(&config_dir);
+        assert!(list_at(&config_dir).unwrap().is_empty());
+
+        let signer_key = SigningKey::from_random();
+        let session_signer = account_sdk::abigen::controller::Signer::Starknet(StarknetSigner {
+            pubkey: NonZero::new(signer_key.verifying_key().scalar()).unwrap(),
+        });
+
+        for chain in [felt!("0x1"), felt!("0x2")] {
+            let session = FullSessionInfo {
+                chain_id: chain,
+                auth: SessionAuth::default(),
+                session: Session::new_wildcard(0, &session_signer, Felt::ZERO).unwrap(),
+                policies: Vec::new(),
+                rpc_url: None,
+                expires_at: 0,
+                authorization: Vec::new(),
+            };
+            store_at(&config_dir, chain, &session).unwrap();
+        }
+
+        assert_eq!(list_at(&config_dir).unwrap().len(), 2);
+
+        assert!(delete_at(&config_dir, felt!("0x1")).unwrap());
+        assert!(!delete_at(&config_dir, felt!("0x1")).unwrap());
+        assert_eq!(list_at(&config_dir).unwrap().len(), 1);
+
+        delete_all_at(&config_dir, username).unwrap();
+        assert!(list_at(&config_dir).unwrap().is_empty());
+    }
+
+    fn preset() -> ControllerConfig {
+        serde_json::from_value(serde_json::json!({
+            "chains": {
+                "SN_MAIN": {
+                    "policies": {
+                        "contracts": {
+                            "0x2": {
+                                "methods": [
+                                    { "entrypoint": "move", "isRequired": true },
+                                    { "entrypoint": "emote" }
+                                ]
+                            },
+                            "0x1": {
+                                "methods": [
+                                    { "entrypoint": "spawn", "isRequired": true },
+                                    { "entrypoint": "invalid€" }
+                                ]
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn policies_from_preset() {
+        let chain = felt!("0x534e5f4d41494e"); // SN_MAIN
+
+        let policies = preset_policies(&preset(), chain, false).unwrap();
+        let methods = policies.iter().map(|p| (p.target, p.method.as_str()));
+        let expected = [
+            (felt!("0x1"), "spawn"),
+            (felt!("0x2"), "emote"),
+            (felt!("0x2"), "move"),
+        ];
+        assert!(methods.eq(expected));
+
+        let policies = preset_policies(&preset(), chain, true).unwrap();
+        let methods = policies.iter().map(|p| (p.target, p.method.as_str()));
+        assert!(methods.eq([(felt!("0x1"), "spawn"), (felt!("0x2"), "move")]));
+
+        // no policies for the chain
+        assert!(preset_policies(&preset(), felt!("0x1"), false).is_err());
+    }
+
+    #[test]
+    fn invalid_required_preset_policy() {
+        let mut preset = preset();
+        let contracts = &mut preset.chains.get_mut("SN_MAIN").unwrap().policies.contracts;
+        contracts.get_mut("0x1").unwrap().methods[1].is_required = true;
+
+        let chain = felt!("0x534e5f4d41494e");
+        let err = preset_policies(&preset, chain, false).unwrap_err();
+        assert!(matches!(err, Error::InvalidMethodName(_)));
+    }
+
+    #[test]
+    fn session_policies_serde() {
+        let call = SessionPolicy::Call(PolicyMethod {
+            target: felt!("0x1"),
+            method: "move".to_string(),
+        });
+        let typed_data = SessionPolicy::TypedData(TypedDataPolicy {
+            domain_hash: felt!("0x2"),
+            type_hash: felt!("0x3"),
+        });
+
+        // call policies are serialized as they were before typed data policies were supported
+        let json = serde_json::to_value(&call).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "target": "0x1", "method": "move" })
+        );
+
+        for policy in [call, typed_data] {
+            let json = serde_json::to_string(&policy).unwrap();
+            assert_eq!(
+                serde_json::from_str::<SessionPolicy>(&json).unwrap(),
+                policy
+            );
+        }
+    }
+
+    #[test]
+    fn typed_data_policy_scope_hash() {
+        let policy = TypedDataPolicy {
+            domain_hash: felt!("0x2"),
+            type_hash: felt!("0x3"),
+        };
+
+        let Policy::TypedData(converted) = Policy::from(&policy) else {
+            panic!("expected a typed data policy");
+        };
+        assert_eq!(
+            converted.scope_hash,
+            poseidon_hash(felt!("0x2"), felt!("0x3"))
+        );
+    }
+
+    fn session_with_policies(policies: Vec<SessionPolicy>) -> FullSessionInfo {
+        let signer_key = SigningKey::from_random();
+        let session_signer = account_sdk::abigen::controller::Signer::Starknet(StarknetSigner {
+            pubkey: NonZero::new(signer_key.verifying_key().scalar()).unwrap(),
+        });
+
+        FullSessionInfo {
+            chain_id: felt!("0x1"),
+            auth: SessionAuth::default(),
+            session: Session::new_wildcard(u64::MAX, &session_signer, Felt::ZERO).unwrap(),
+            policies,
+            rpc_url: None,
+            expires_at: u64::MAX,
+            authorization: Vec::new(),
+        }
+    }
+
+    fn call(to: Felt, method: &str) -> Call {
+        Call {
+            to,
+            selector: get_selector_from_name(method).unwrap(),
+            calldata: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn check_calls_coverage() {
+        let policy = PolicyMethod {
+            target: felt!("0x1"),
+            method: "move".to_string(),
+        };
+        let session = session_with_policies(vec![
+            SessionPolicy::Call(policy.clone()),
+            SessionPolicy::TypedData(TypedDataPolicy {
+                domain_hash: felt!("0x2"),
+                type_hash: felt!("0x3"),
+            }),
+        ]);
+
+        let calls = [
+            call(felt!("0x1"), "move"),
+            call(felt!("0x1"), "attack"),
+            call(felt!("0x2"), "move"),
+        ];
+        let coverage = check_calls(&session, &calls)
+            .into_iter()
+            .map(|check| check.coverage)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            coverage,
+            vec![
+                CallCoverage::Allowed(policy),
+                CallCoverage::UnknownMethod,
+                CallCoverage::UnknownContract,
+            ]
+        );
+    }
+
+    #[test]
+    fn check_calls_normalizes_addresses() {
+        let target = Felt::from_hex("0x0000abc").unwrap();
+        let session = session_with_policies(vec![SessionPolicy::Call(PolicyMethod {
+            target,
+            method: "move".to_string(),
+        })]);
+
+        let checks = check_calls(&session, &[call(Felt::from_hex("0xabc").unwrap(), "move")]);
+        assert!(checks[0].coverage.is_allowed());
+    }
+
+    #[test]
+    fn check_calls_without_policies() {
+        let session = session_with_policies(Vec::new());
+
+        let checks = check_calls(&session, &[call(felt!("0x1"), "move")]);
+        assert_eq!(checks[0].coverage, CallCoverage::UnknownPolicies);
+        assert!(ensure_calls_allowed(&session, &[call(felt!("0x1"), "move")]).is_err());
+    }
+
+    #[test]
+    fn calls_allowed_by_session() {
+        let session = session_with_policies(vec![SessionPolicy::Call(PolicyMethod {
+            target: felt!("0x1"),
+            method: "move".to_string(),
+        })]);
+
+        assert!(ensure_calls_allowed(&session, &[call(felt!("0x1"), "move")]).is_ok());
+        assert!(ensure_calls_allowed(&session, &[call(felt!("0x2"), "move")]).is_err());
+
+        let calls = [call(felt!("0x1"), "move"), call(felt!("0x1"), "attack")];
+        assert!(ensure_calls_allowed(&session, &calls).is_err());
+    }
+
+    #[test]
+    fn chain_id_names() {
+        assert_eq!(chain_id_name(felt!("0x534e5f4d41494e")), "SN_MAIN");
+        assert_eq!(chain_id_name(Felt::MAX), format!("{:#x}", Felt::MAX));
+    }
+
+    #[test]
+    fn max_lifetime_is_validated() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+
+        let expires_at = max_expires_at(Duration::from_secs(3600), now).unwrap();
+        assert_eq!(expires_at, 4_600);
+
+        assert!(max_expires_at(Duration::from_secs(1), now).is_err());
+        assert!(max_expires_at(Duration::MAX, now).is_err());
+    }
+
     #[test]
     fn store_session_unauthenticated() {
         // Clear SLOT_AUTH to ensure we're testing unauthenticated behavior
@@ -548,6 +1507,10 @@ mod tests {
             chain_id: chain,
             auth: SessionAuth::default(),
             session: Session::new_wildcard(0, &session_signer, Felt::ZERO).unwrap(),
+            policies: Vec::new(),
+            rpc_url: None,
+            expires_at: 0,
+            authorization: Vec::new(),
         };
 
         let err = store_at(config_dir, chain, &session).unwrap_err();
@@ -557,11 +1520,23 @@ mod tests {
     #[tokio::test]
     async fn test_callback_server() {
         let (tx, mut rx) = channel(1);
-        let server = super::callback_server(tx).expect("failed to create server");
-
-        // get the callback url
-        let port = server.local_addr().unwrap().port();
-        let url = format!("http://localhost:{port}/callback");
+        let server =
+            super::callback_server(tx, &CallbackAddr::default()).expect("failed to create server");
+
+        // get the callback url handed to the keychain
+        let input = SessionCreationInput {
+            public_key: Felt::ONE,
+            username: "johnsmith",
+            rpc_url: "http://localhost:5050",
+            policies: &[],
+            max_expires_at: None,
+        };
+        let page = super::session_creation_callback_url(input, &server).unwrap();
+        let url = page
+            .query_pairs()
+            .find(|(key, _)| key == "callback_uri")
+            .map(|(_, value)| value.into_owned())
+            .expect("missing callback uri");
 
         // start the callback server
         tokio::spawn(server.start());
@@ -594,6 +1569,7 @@ mod tests {
             )),
             expires_at: "2023-12-31T23:59:59Z".to_string(),
             already_registered: false,
+            authorization: vec![felt!("0x1"), felt!("0x2")],
         };
 
         let encoded = original.to_encoded().unwrap();
@@ -605,5 +1581,6 @@ mod tests {
         assert_eq!(decoded.transaction_hash, original.transaction_hash);
         assert_eq!(decoded.expires_at, original.expires_at);
         assert_eq!(decoded.already_registered, original.already_registered);
+        assert_eq!(decoded.authorization, original.authorization);
     }
 }
@@ -0,0 +1,245 @@
+//! On-disk storage of the session files.
+//!
+//! The session files hold the private key of the session signer, so they are encrypted following
+//! the [`CredentialsStore`] used for the credentials: with the user's passphrase for the encrypted
+//! file store, or with a key kept in the OS keyring for the keyring store. On Unix, the files are
+//! only readable by their owner.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use super::FullSessionInfo;
+use crate::credential::CredentialsStore;
+use crate::encryption::{self, EncryptedData};
+use crate::error::Error;
+use crate::utils;
+
+const PASSPHRASE_PROMPT: &str = "Sessions passphrase";
+
+/// An encrypted session file, tagged with where the encryption key comes from.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "encryption", rename_all = "kebab-case")]
+enum EncryptedSession {
+    /// Encrypted with the user's passphrase.
+    Passphrase(EncryptedData),
+    /// Encrypted with a random key stored in the OS keyring.
+    Keyring(EncryptedData),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SessionFile {
+    Encrypted(EncryptedSession),
+    Plaintext(Box<FullSessionInfo>),
+}
+
+/// Writes the session to `path`, encrypted according to `store`. `config_dir` is the directory
+/// of the profile the session belongs to.
+#[cfg_attr(not(feature = "keyring"), allow(unused_variables))]
+pub(super) fn write(
+    config_dir: &Path,
+    path: &Path,
+    session: &FullSessionInfo,
+    store: CredentialsStore,
+) -> Result<(), Error> {
+    let contents = match store {
+        CredentialsStore::File => serde_json::to_string_pretty(session)?,
+
+        CredentialsStore::EncryptedFile => {
+            let passphrase = encryption::passphrase(PASSPHRASE_PROMPT, true)?;
+            let encrypted = EncryptedData::encrypt(&serde_json::to_vec(session)?, &passphrase)?;
+            serde_json::to_string_pretty(&EncryptedSession::Passphrase(encrypted))?
+        }
+
+        #[cfg(feature = "keyring")]
+        CredentialsStore::Keyring => match keyring::get_or_create_key(config_dir) {
+            Ok(key) => {
+                let encrypted = EncryptedData::encrypt(&serde_json::to_vec(session)?, &key)?;
+                serde_json::to_string_pretty(&EncryptedSession::Keyring(encrypted))?
+            }
+            Err(err) => {
+                tracing::warn!(%err, "Keyring unavailable, storing the session unencrypted.");
+                serde_json::to_string_pretty(session)?
+            }
+        },
+    };
+
+    Ok(utils::write_private(path, contents.as_bytes())?)
+}
+
+/// Reads the session stored at `path`, decrypting it if needed. `config_dir` is the directory
+/// of the profile the session belongs to.
+#[cfg_attr(not(feature = "keyring"), allow(unused_variables))]
+pub(super) fn read(config_dir: &Path, path: &Path) -> Result<FullSessionInfo, Error> {
+    let contents = fs::read_to_string(path)?;
+
+    match serde_json::from_str::<SessionFile>(&contents)? {
+        SessionFile::Plaintext(session) => Ok(*session),
+        SessionFile::Encrypted(encrypted) => decrypt(config_dir, encrypted),
+    }
+}
+
+/// Deletes the key used to encrypt the sessions of the profile, if any.
+#[cfg_attr(not(feature = "keyring"), allow(unused_variables))]
+pub(super) fn delete_key(config_dir: &Path) -> Result<(), Error> {
+    #[cfg(feature = "keyring")]
+    keyring::delete(config_dir)?;
+
+    Ok(())
+}
+
+/// Encodes the session to be moved to another machine, encrypted with a passphrase.
+pub(super) fn export(session: &FullSessionInfo) -> Result<String, Error> {
+    let passphrase = encryption::passphrase("Export passphrase", true)?;
+    let encrypted = EncryptedData::encrypt(&serde_json::to_vec(session)?, &passphrase)?;
+    Ok(serde_json::to_string_pretty(
+        &EncryptedSession::Passphrase(encrypted),
+    )?)
+}
+
+/// Decodes a session exported with [`export`]. Plaintext session files are accepted as well.
+pub(super) fn import(contents: &str) -> Result<FullSessionInfo, Error> {
+    match serde_json::from_str::<SessionFile>(contents)? {
+        SessionFile::Plaintext(session) => Ok(*session),
+        SessionFile::Encrypted(EncryptedSession::Passphrase(encrypted)) => {
+            let passphrase = encryption::passphrase("Export passphrase", false)?;
+            Ok(serde_json::from_slice(&encrypted.decrypt(&passphrase)?)?)
+        }
+        SessionFile::Encrypted(EncryptedSession::Keyring(_)) => Err(anyhow!(
+            "the session is encrypted with a key from the keyring of another machine, export it \
+             with `slot sessions export` instead"
+        )
+        .into()),
+    }
+}
+
+#[cfg_attr(not(feature = "keyring"), allow(unused_variables))]
+fn decrypt(config_dir: &Path, encrypted: EncryptedSession) -> Result<FullSessionInfo, Error> {
+    let decrypted = match encrypted {
+        EncryptedSession::Passphrase(encrypted) => {
+            let passphrase = encryption::passphrase(PASSPHRASE_PROMPT, false)?;
+            encrypted.decrypt(&passphrase)?
+        }
+
+        #[cfg(feature = "keyring")]
+        EncryptedSession::Keyring(encrypted) => {
+            let key = keyring::key(config_dir)?
+                .ok_or_else(|| anyhow!("the key of the session is missing from the keyring"))?;
+            encrypted.decrypt(&key)?
+        }
+
+        #[cfg(not(feature = "keyring"))]
+        EncryptedSession::Keyring(_) => {
+            return Err(anyhow!(
+                "the session is encrypted with a key from the keyring, which requires the \
+                 `keyring` feature"
+            )
+            .into())
+        }
+    };
+
+    Ok(serde_json::from_slice(&decrypted)?)
+}
+
+#[cfg(feature = "keyring")]
+mod keyring {
+    use std::fs;
+    use std::path::Path;
+
+    use anyhow::anyhow;
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use keyring::Entry;
+    use rand::RngCore;
+
+    use crate::error::Error;
+
+    /// The service name of the keyring entries.
+    const SERVICE: &str = "slot-sessions";
+    /// A file, in the profile directory, marking that the profile has a key in the keyring.
+    const KEYRING_REFERENCE_FILE: &str = "sessions.keyring";
+
+    /// Returns the key used to encrypt the sessions of the profile, if any.
+    pub(super) fn key(config_dir: &Path) -> Result<Option<String>, Error> {
+        match entry(config_dir)?.get_password() {
+            Ok(key) => Ok(Some(key)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(err) => Err(keyring_error(err)),
+        }
+    }
+
+    /// Returns the key used to encrypt the sessions of the profile, generating a random one if
+    /// the profile doesn't have one yet.
+    pub(super) fn get_or_create_key(config_dir: &Path) -> Result<String, Error> {
+        if let Some(key) = key(config_dir)? {
+            return Ok(key);
+        }
+
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+
+        let key = STANDARD.encode(bytes);
+        entry(config_dir)?
+            .set_password(&key)
+            .map_err(keyring_error)?;
+
+        fs::write(config_dir.join(KEYRING_REFERENCE_FILE), SERVICE)?;
+        Ok(key)
+    }
+
+    /// Deletes the key used to encrypt the sessions of the profile, if any.
+    pub(super) fn delete(config_dir: &Path) -> Result<(), Error> {
+        let path = config_dir.join(KEYRING_REFERENCE_FILE);
+        if !path.exists() {
+            return Ok(());
+        }
+
+        match entry(config_dir)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(err) => return Err(keyring_error(err)),
+        }
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// The keyring entries are identified by the profile directory, so that each profile has its
+    /// own key.
+    fn entry(config_dir: &Path) -> Result<Entry, Error> {
+        Entry::new(SERVICE, &config_dir.display().to_string()).map_err(keyring_error)
+    }
+
+    fn keyring_error(err: keyring::Error) -> Error {
+        anyhow!("keyring error: {err}").into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::encryption::PASSPHRASE_ENV_VAR;
+
+    #[test]
+    fn encrypted_session_file_format() {
+        let data = EncryptedData::encrypt(b"session", "passphrase").unwrap();
+        let json = serde_json::to_value(EncryptedSession::Passphrase(data)).unwrap();
+
+        assert_eq!(json["encryption"], "passphrase");
+        assert!(json["ciphertext"].is_string());
+    }
+
+    #[test]
+    fn import_encrypted_with_wrong_passphrase() {
+        env::set_var(PASSPHRASE_ENV_VAR, "passphrase");
+
+        let data = EncryptedData::encrypt(b"{}", "another passphrase").unwrap();
+        let exported = serde_json::to_string(&EncryptedSession::Passphrase(data)).unwrap();
+
+        let err = import(&exported).unwrap_err();
+        assert!(matches!(err, Error::InvalidPassphrase));
+    }
+}
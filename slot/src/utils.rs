@@ -1,5 +1,9 @@
 use regex::Regex;
-use std::{fs, path::PathBuf, sync::OnceLock};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 
 /// The default directory name where the Slot-generated files (e.g credentials/session keys) are stored.
 const SLOT_DIR: &str = "slot";
@@ -27,6 +31,34 @@ pub fn config_dir() -> PathBuf {
     }
 }
 
+/// Writes `contents` to `path`, only readable and writable by the owner of the file. On Unix, the
+/// parent directory is also restricted to its owner.
+#[cfg(unix)]
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    if let Some(parent) = path.parent() {
+        fs::set_permissions(parent, fs::Permissions::from_mode(0o700))?;
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+
+    // The mode is only applied to new files.
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    fs::write(path, contents)
+}
+
 /// Validates if the provided string is a valid email address format.
 ///
 /// Uses a regex pattern to check for basic email format:
@@ -61,6 +93,28 @@ mod tests {
         assert!(path.ends_with(SLOT_DIR));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn write_private_restricts_permissions() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = super::config_dir().join("account");
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("secret.json");
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        super::write_private(&path, b"secret").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let mode = fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        assert_eq!(fs::read(&path).unwrap(), b"secret");
+    }
+
     #[test]
     fn test_valid_emails() {
         assert!(super::is_valid_email("test@example.com"));
@@ -0,0 +1,325 @@
+//! Installation of the release binaries of the Slot CLI, without relying on `slotup`.
+//!
+//! The release archive of the current target is downloaded from GitHub, verified against the
+//! checksum published next to it, and its binary atomically replaces the running executable. The
+//! replaced binary is kept to roll back to.
+
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use sha2::{Digest, Sha256};
+
+/// The URL of the releases of the Slot CLI, the assets of a version being under `v<version>/`.
+pub const RELEASES_URL: &str = "https://github.com/cartridge-gg/slot/releases/download";
+
+/// Suffix of the copy of the binary kept for rollbacks
+const PREVIOUS_BINARY_SUFFIX: &str = "previous";
+
+/// Downloads and installs the release binaries of the Slot CLI.
+#[derive(Debug, Clone)]
+pub struct Updater {
+    releases_url: String,
+    binary: PathBuf,
+}
+
+impl Updater {
+    /// Creates an updater replacing the running executable with the official releases.
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            releases_url: RELEASES_URL.to_string(),
+            binary: env::current_exe()?,
+        })
+    }
+
+    /// Downloads the releases from `url` instead of GitHub.
+    pub fn releases_url(mut self, url: impl Into<String>) -> Self {
+        self.releases_url = url.into();
+        self
+    }
+
+    /// Replaces the binary at `path` instead of the running executable.
+    pub fn binary(mut self, path: impl Into<PathBuf>) -> Self {
+        self.binary = path.into();
+        self
+    }
+
+    /// Installs the given version, keeping the replaced binary for [`Updater::rollback`].
+    pub async fn install(&self, version: &str) -> Result<()> {
+        let version = version.trim().trim_start_matches('v');
+        let asset = asset_name(version)?;
+        let url = format!(
+            "{}/v{version}/{asset}",
+            self.releases_url.trim_end_matches('/')
+        );
+
+        let client = reqwest::Client::new();
+        let archive = download(&client, &url).await?;
+        let checksum = download(&client, &format!("{url}.sha256"))
+            .await
+            .context("Failed to download the checksum of the release")?;
+
+        verify_checksum(&archive, &String::from_utf8_lossy(&checksum))?;
+        let binary = extract_binary(&archive)?;
+        replace_binary(&self.binary, &binary)
+    }
+
+    /// Restores the binary replaced by the last update. Rolling back again undoes the rollback.
+    pub fn rollback(&self) -> Result<()> {
+        rollback_binary(&self.binary)
+    }
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let response = client.get(url).send().await?.error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// The name of the release archive of `version` for the current target, as published by the
+/// release workflow.
+fn asset_name(version: &str) -> Result<String> {
+    let (platform, ext) = match env::consts::OS {
+        "linux" => ("linux", "tar.gz"),
+        "macos" => ("darwin", "tar.gz"),
+        "windows" => ("win32", "zip"),
+        os => return Err(anyhow!("no release of the Slot CLI for {os}")),
+    };
+
+    let arch = match env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        arch => return Err(anyhow!("no release of the Slot CLI for {arch}")),
+    };
+
+    Ok(format!("slot_v{version}_{platform}_{arch}.{ext}"))
+}
+
+/// Checks `data` against a `sha256sum` formatted checksum.
+fn verify_checksum(data: &[u8], checksum: &str) -> Result<()> {
+    let expected = checksum
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| anyhow!("the published checksum is empty"))?;
+    let actual = format!("{:x}", Sha256::digest(data));
+
+    ensure!(
+        actual.eq_ignore_ascii_case(expected),
+        "checksum mismatch for the downloaded release (expected {expected}, got {actual})"
+    );
+
+    Ok(())
+}
+
+/// Extracts the slot binary from a release archive.
+#[cfg(not(windows))]
+fn extract_binary(archive: &[u8]) -> Result<Vec<u8>> {
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.file_name().is_some_and(|name| name == "slot") {
+            let mut binary = Vec::new();
+            entry.read_to_end(&mut binary)?;
+            return Ok(binary);
+        }
+    }
+
+    Err(anyhow!(
+        "the release archive doesn't contain the slot binary"
+    ))
+}
+
+/// Extracts the slot binary from a release archive.
+#[cfg(windows)]
+fn extract_binary(archive: &[u8]) -> Result<Vec<u8>> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(archive))?;
+    let mut entry = archive
+        .by_name("slot.exe")
+        .context("the release archive doesn't contain the slot binary")?;
+
+    let mut binary = Vec::new();
+    entry.read_to_end(&mut binary)?;
+    Ok(binary)
+}
+
+/// Path of the copy of `binary` kept for rollbacks
+fn previous_binary_path(binary: &Path) -> PathBuf {
+    let mut name = binary.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(PREVIOUS_BINARY_SUFFIX);
+    binary.with_file_name(name)
+}
+
+/// Atomically replaces `binary` with `contents`, keeping the replaced binary for rollbacks.
+fn replace_binary(binary: &Path, contents: &[u8]) -> Result<()> {
+    let mut name = binary.file_name().unwrap_or_default().to_os_string();
+    name.push(".new");
+    let new = binary.with_file_name(name);
+
+    // Written next to the binary, so that it can be renamed over it.
+    fs::write(&new, contents)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&new, fs::Permissions::from_mode(0o755))?;
+    }
+
+    if binary.exists() {
+        let previous = previous_binary_path(binary);
+
+        // A running executable can't be overwritten on Windows, but it can be renamed.
+        #[cfg(windows)]
+        {
+            let _ = fs::remove_file(&previous);
+            fs::rename(binary, &previous)?;
+        }
+
+        #[cfg(not(windows))]
+        fs::copy(binary, &previous)?;
+    }
+
+    fs::rename(&new, binary)?;
+    Ok(())
+}
+
+/// Restores the copy of `binary` kept by the last update, and keeps the replaced binary as the
+/// one to roll back to, so that a rollback can be undone by rolling back again
+fn rollback_binary(binary: &Path) -> Result<()> {
+    let previous = previous_binary_path(binary);
+    ensure!(
+        previous.exists(),
+        "no previous version of {} to roll back to",
+        binary.display()
+    );
+
+    let swap = binary.with_extension("rollback");
+    fs::rename(binary, &swap)?;
+    fs::rename(&previous, binary)?;
+    fs::rename(&swap, &previous)?;
+
+    Ok(())
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use std::io::Write;
+
+    use axum::routing::get;
+    use axum::Router;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    const VERSION: &str = "0.99.0";
+
+    fn release_archive(binary: &[u8]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(binary.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, "slot", binary).unwrap();
+
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    /// Serves a release of [`VERSION`] with the given archive and checksum, and returns the URL of
+    /// the releases.
+    async fn serve_release(archive: Vec<u8>, checksum: String) -> String {
+        let asset = asset_name(VERSION).unwrap();
+        let router = Router::new()
+            .route(
+                &format!("/v{VERSION}/{asset}"),
+                get(move || async move { archive }),
+            )
+            .route(
+                &format!("/v{VERSION}/{asset}.sha256"),
+                get(move || async move { checksum }),
+            );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        format!("http://{addr}")
+    }
+
+    fn checksum(data: &[u8]) -> String {
+        format!(
+            "{:x}  {}\n",
+            Sha256::digest(data),
+            asset_name(VERSION).unwrap()
+        )
+    }
+
+    #[tokio::test]
+    async fn install_and_rollback() {
+        let archive = release_archive(b"new");
+        let url = serve_release(archive.clone(), checksum(&archive)).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("slot");
+        fs::write(&binary, "old").unwrap();
+
+        let updater = Updater::new().unwrap().releases_url(url).binary(&binary);
+        updater.install(&format!("v{VERSION}")).await.unwrap();
+
+        assert_eq!(fs::read(&binary).unwrap(), b"new");
+        assert_eq!(fs::read(previous_binary_path(&binary)).unwrap(), b"old");
+
+        updater.rollback().unwrap();
+        assert_eq!(fs::read(&binary).unwrap(), b"old");
+
+        // rolling back again restores the update
+        updater.rollback().unwrap();
+        assert_eq!(fs::read(&binary).unwrap(), b"new");
+    }
+
+    #[tokio::test]
+    async fn install_with_invalid_checksum() {
+        let archive = release_archive(b"new");
+        let url = serve_release(archive, checksum(b"tampered")).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("slot");
+        fs::write(&binary, "old").unwrap();
+
+        let updater = Updater::new().unwrap().releases_url(url).binary(&binary);
+        let err = updater.install(VERSION).await.unwrap_err();
+
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert_eq!(fs::read(&binary).unwrap(), b"old");
+        assert!(!previous_binary_path(&binary).exists());
+    }
+
+    #[tokio::test]
+    async fn install_missing_release() {
+        let url = serve_release(Vec::new(), String::new()).await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let updater = Updater::new()
+            .unwrap()
+            .releases_url(url)
+            .binary(dir.path().join("slot"));
+
+        assert!(updater.install("0.1.0").await.is_err());
+    }
+
+    #[test]
+    fn rollback_without_previous_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("slot");
+        fs::File::create(&binary)
+            .unwrap()
+            .write_all(b"current")
+            .unwrap();
+
+        assert!(rollback_binary(&binary).is_err());
+        assert_eq!(fs::read(&binary).unwrap(), b"current");
+    }
+}
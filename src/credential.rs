@@ -0,0 +1,579 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{env, fs};
+
+use crate::account::AccountInfo;
+use crate::encryption::{self, EncryptedData};
+use crate::error::Error;
+use crate::profile::{self};
+use crate::utils;
+
+#[cfg(feature = "keyring")]
+mod keyring;
+
+const CREDENTIALS_FILE: &str = "credentials.json";
+const ENCRYPTED_CREDENTIALS_FILE: &str = "credentials.enc";
+
+/// The environment variable used to select where the credentials are stored.
+pub const STORE_ENV_VAR: &str = "SLOT_CREDENTIALS_STORE";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AccessToken {
+    pub token: String,
+    pub r#type: String,
+    /// The expiration time of the token, as a UNIX timestamp in seconds. `None` if unknown, e.g.
+    /// for tokens stored by previous versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    /// The token used to get a new access token once this one expires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+impl AccessToken {
+    /// Returns whether the token expires in less than `margin` from now. Tokens with an unknown
+    /// expiration time are assumed to never expire.
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        let Some(expires_at) = self.expires_at else {
+            return false;
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        now + margin >= Duration::from_secs(expires_at)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Credentials {
+    pub account: AccountInfo,
+    pub access_token: AccessToken,
+}
+
+/// Where the credentials are stored.
+///
+/// Selected with the `SLOT_CREDENTIALS_STORE` environment variable (`file`, `encrypted-file` or
+/// `keyring`). Defaults to the OS keyring when built with the `keyring` feature, and to the
+/// plaintext file otherwise.
+///
+/// Credentials stored in the plaintext file are automatically migrated to the selected store
+/// the first time they are loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialsStore {
+    /// A plaintext JSON file in the profile directory.
+    File,
+    /// A file in the profile directory, encrypted with the passphrase set in the
+    /// `SLOT_PASSPHRASE` environment variable or prompted to the user.
+    EncryptedFile,
+    /// The OS secret service (e.g. macOS Keychain, Windows Credential Manager, Secret Service
+    /// on Linux). Falls back to the plaintext file if the keyring is unavailable.
+    #[cfg(feature = "keyring")]
+    Keyring,
+}
+
+impl CredentialsStore {
+    /// Returns the store selected by the `SLOT_CREDENTIALS_STORE` environment variable.
+    pub fn from_env() -> Result<Self, Error> {
+        match env::var(STORE_ENV_VAR).as_deref() {
+            Err(_) => Ok(Self::default()),
+            Ok("file") => Ok(Self::File),
+            Ok("encrypted-file") => Ok(Self::EncryptedFile),
+            #[cfg(feature = "keyring")]
+            Ok("keyring") => Ok(Self::Keyring),
+            Ok(other) => Err(anyhow!("unsupported credentials store `{other}`").into()),
+        }
+    }
+}
+
+impl Default for CredentialsStore {
+    #[cfg(feature = "keyring")]
+    fn default() -> Self {
+        Self::Keyring
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    fn default() -> Self {
+        Self::File
+    }
+}
+
+impl Credentials {
+    pub fn new(account: AccountInfo, access_token: AccessToken) -> Self {
+        Self {
+            account,
+            access_token,
+        }
+    }
+
+    /// Load the credentials of the currently authenticated user of the active profile.
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if no user has authenticated yet, or if
+    /// the credentials file are invalid or missing.
+    ///
+    pub fn load() -> Result<Self, Error> {
+        Self::load_at(profile::active_dir()?)
+    }
+
+    /// Load the credentials of the authenticated user of the profile `name`.
+    pub fn load_profile(name: &str) -> Result<Self, Error> {
+        Self::load_at(profile::dir(name)?)
+    }
+
+    /// Store the credentials of an authenticated user in the active profile. Returns the path to
+    /// the stored credentials file.
+    pub fn store(&self) -> Result<PathBuf, Error> {
+        Self::store_at(profile::active_dir()?, self)
+    }
+
+    pub(crate) fn store_at<P: AsRef<Path>>(
+        config_dir: P,
+        credentials: &Self,
+    ) -> Result<PathBuf, Error> {
+        Self::store_in(config_dir, credentials, CredentialsStore::from_env()?)
+    }
+
+    pub(crate) fn load_at<P: AsRef<Path>>(config_dir: P) -> Result<Credentials, Error> {
+        Self::load_from(config_dir, CredentialsStore::from_env()?)
+    }
+
+    fn store_in<P: AsRef<Path>>(
+        config_dir: P,
+        credentials: &Self,
+        store: CredentialsStore,
+    ) -> Result<PathBuf, Error> {
+        let config_dir = config_dir.as_ref();
+        // create the dir paths if it doesn't yet exist
+        fs::create_dir_all(config_dir)?;
+
+        let path = match store {
+            CredentialsStore::File => {
+                let path = get_file_path(config_dir);
+                let content = serde_json::to_string_pretty(credentials)?;
+                utils::write_private(&path, content.as_bytes())?;
+                return Ok(path);
+            }
+
+            CredentialsStore::EncryptedFile => {
+                let passphrase = encryption::passphrase("Credentials passphrase", true)?;
+                let content = serde_json::to_vec(credentials)?;
+                let encrypted = EncryptedData::encrypt(&content, &passphrase)?;
+
+                let path = config_dir.join(ENCRYPTED_CREDENTIALS_FILE);
+                let content = serde_json::to_string_pretty(&encrypted)?;
+                utils::write_private(&path, content.as_bytes())?;
+                path
+            }
+
+            #[cfg(feature = "keyring")]
+            CredentialsStore::Keyring => match keyring::store(config_dir, credentials) {
+                Ok(path) => path,
+                Err(err) => {
+                    tracing::warn!(%err, "Keyring unavailable, storing credentials in a file.");
+                    return Self::store_in(config_dir, credentials, CredentialsStore::File);
+                }
+            },
+        };
+
+        // The credentials are now safely stored, so remove any plaintext copy left behind.
+        let plaintext = get_file_path(config_dir);
+        if plaintext.exists() {
+            fs::remove_file(plaintext)?;
+        }
+
+        Ok(path)
+    }
+
+    fn load_from<P: AsRef<Path>>(
+        config_dir: P,
+        store: CredentialsStore,
+    ) -> Result<Credentials, Error> {
+        if let Ok(slot_auth) = env::var("SLOT_AUTH") {
+            // Try parsing from environment variable first
+            return serde_json::from_str::<Credentials>(&slot_auth)
+                .map_err(|_| Error::MalformedCredentials);
+        }
+
+        let config_dir = config_dir.as_ref();
+
+        let credentials = match store {
+            CredentialsStore::File => return load_plaintext(config_dir),
+
+            CredentialsStore::EncryptedFile => {
+                let path = config_dir.join(ENCRYPTED_CREDENTIALS_FILE);
+                if path.exists() {
+                    Some(load_encrypted(&path)?)
+                } else {
+                    None
+                }
+            }
+
+            #[cfg(feature = "keyring")]
+            CredentialsStore::Keyring => match keyring::load(config_dir) {
+                Ok(credentials) => credentials,
+                Err(err) => {
+                    tracing::warn!(%err, "Keyring unavailable, loading credentials from a file.");
+                    return load_plaintext(config_dir);
+                }
+            },
+        };
+
+        match credentials {
+            Some(credentials) => Ok(credentials),
+            // Migrate the credentials stored by previous versions, if any.
+            None => {
+                let credentials = load_plaintext(config_dir)?;
+                Self::store_in(config_dir, &credentials, store)?;
+                Ok(credentials)
+            }
+        }
+    }
+}
+
+/// Replaces the access token of the credentials stored in the active profile, if they are
+/// authenticated with `old`. Used to persist the tokens refreshed by the API client.
+pub(crate) fn update_stored_token(old: &AccessToken, new: &AccessToken) -> Result<(), Error> {
+    // Credentials set through the environment are never written to disk.
+    if env::var("SLOT_AUTH").is_ok() {
+        return Ok(());
+    }
+
+    let config_dir = profile::active_dir()?;
+    let mut credentials = match Credentials::load_at(&config_dir) {
+        Ok(credentials) => credentials,
+        Err(Error::Unauthorized) => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    if credentials.access_token.token != old.token {
+        return Ok(());
+    }
+
+    credentials.access_token = new.clone();
+    Credentials::store_at(config_dir, &credentials)?;
+    Ok(())
+}
+
+/// Get the path to the credentials file.
+pub fn get_file_path<P: AsRef<Path>>(config_dir: P) -> PathBuf {
+    config_dir.as_ref().join(CREDENTIALS_FILE)
+}
+
+/// Returns whether credentials are stored in `config_dir`, in any of the stores.
+pub(crate) fn exists_at(config_dir: impl AsRef<Path>) -> bool {
+    let config_dir = config_dir.as_ref();
+
+    #[cfg(feature = "keyring")]
+    if keyring::exists(config_dir) {
+        return true;
+    }
+
+    get_file_path(config_dir).exists() || config_dir.join(ENCRYPTED_CREDENTIALS_FILE).exists()
+}
+
+/// Deletes the credentials stored in `config_dir`, from all the stores.
+pub(crate) fn delete_at(config_dir: impl AsRef<Path>) -> Result<(), Error> {
+    let config_dir = config_dir.as_ref();
+
+    #[cfg(feature = "keyring")]
+    keyring::delete(config_dir)?;
+
+    for path in [
+        get_file_path(config_dir),
+        config_dir.join(ENCRYPTED_CREDENTIALS_FILE),
+    ] {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn load_plaintext(config_dir: &Path) -> Result<Credentials, Error> {
+    let path = get_file_path(config_dir);
+
+    if !path.exists() {
+        return Err(Error::Unauthorized);
+    }
+
+    let content = fs::read_to_string(&path)?;
+
+    match serde_json::from_str::<Credentials>(&content) {
+        Ok(credentials) => Ok(credentials),
+        Err(_) => {
+            quarantine(&path)?;
+            Err(Error::MalformedCredentials)
+        }
+    }
+}
+
+fn load_encrypted(path: &Path) -> Result<Credentials, Error> {
+    let content = fs::read_to_string(path)?;
+
+    let Ok(encrypted) = serde_json::from_str::<EncryptedData>(&content) else {
+        quarantine(path)?;
+        return Err(Error::MalformedCredentials);
+    };
+
+    let passphrase = encryption::passphrase("Credentials passphrase", false)?;
+    let decrypted = encrypted.decrypt(&passphrase)?;
+
+    serde_json::from_slice(&decrypted).map_err(|_| Error::MalformedCredentials)
+}
+
+/// Moves a malformed credentials file aside, so that the user can login again without losing
+/// the original file.
+fn quarantine(path: &Path) -> Result<PathBuf, Error> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mut file_name = path.file_name().expect("qed; file path").to_os_string();
+    file_name.push(format!(".malformed-{timestamp}"));
+
+    let quarantined = path.with_file_name(file_name);
+    fs::rename(path, &quarantined)?;
+    tracing::warn!(path = %quarantined.display(), "Malformed credentials file moved aside.");
+
+    Ok(quarantined)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use serde_json::{json, Value};
+
+    use crate::account::AccountInfo;
+    use crate::credential::{
+        AccessToken, Credentials, CredentialsStore, CREDENTIALS_FILE, ENCRYPTED_CREDENTIALS_FILE,
+    };
+    use crate::encryption::PASSPHRASE_ENV_VAR;
+    use crate::{utils, Error};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use std::{env, fs};
+
+    // This test is to make sure that changes made to the `Credentials` struct doesn't
+    // introduce breaking changes to the serde format.
+    #[test]
+    fn test_rt_static_format() {
+        let json = json!({
+            "account": {
+                "id": "foo",
+                "username": "username",
+                "controllers": [
+                    {
+                        "id": "foo",
+                        "address": "0x12345"
+                    }
+                ],
+                "credentials": [
+                    {
+                        "id": "foobar",
+                        "publicKey": "mypublickey"
+                    }
+                ]
+            },
+            "access_token": {
+                "token": "oauthtoken",
+                "type": "bearer"
+            }
+        });
+
+        let credentials: Credentials = serde_json::from_value(json.clone()).unwrap();
+
+        assert_eq!(credentials.account.id, "foo".to_string());
+        assert_eq!(credentials.account.username, "username".to_string());
+        assert_eq!(credentials.account.credentials[0].id, "foobar");
+        assert_eq!(credentials.account.credentials[0].public_key, "mypublickey");
+        assert_eq!(credentials.access_token.token, "oauthtoken");
+        assert_eq!(credentials.access_token.r#type, "bearer");
+
+        let credentials_serialized: Value = serde_json::to_value(&credentials).unwrap();
+        assert_eq!(json, credentials_serialized);
+    }
+
+    #[test]
+    fn loading_malformed_credentials() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        env::remove_var("SLOT_AUTH");
+
+        let malformed_cred = json!({
+            "access_token": "mytoken",
+            "token_type": "mytokentype"
+        });
+
+        let dir = utils::config_dir();
+        let path = dir.join(CREDENTIALS_FILE);
+        fs::create_dir_all(&dir).expect("failed to create intermediary dirs");
+        fs::write(path, serde_json::to_vec(&malformed_cred).unwrap()).unwrap();
+
+        let result = Credentials::load_at(&dir);
+        assert_matches!(result, Err(Error::MalformedCredentials));
+
+        // the malformed file must be moved aside instead of deleted
+        let quarantined = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .find(|entry| {
+                let name = entry.file_name();
+                name.to_string_lossy()
+                    .starts_with(&format!("{CREDENTIALS_FILE}.malformed-"))
+            })
+            .expect("malformed credentials must be quarantined");
+
+        assert!(!dir.join(CREDENTIALS_FILE).exists());
+        let content: Value =
+            serde_json::from_slice(&fs::read(quarantined.path()).unwrap()).unwrap();
+        assert_eq!(content, malformed_cred);
+    }
+
+    #[test]
+    fn loading_non_existent_credentials() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        env::remove_var("SLOT_AUTH");
+
+        let dir = utils::config_dir();
+        let err = Credentials::load_at(dir).unwrap_err();
+        assert!(err.to_string().contains("No credentials found"))
+    }
+
+    #[test]
+    fn credentials_rt() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        env::remove_var("SLOT_AUTH");
+
+        let config_dir = utils::config_dir();
+
+        let access_token = AccessToken {
+            token: "mytoken".to_string(),
+            r#type: "Bearer".to_string(),
+            expires_at: None,
+            refresh_token: None,
+        };
+
+        let expected = Credentials::new(AccountInfo::default(), access_token);
+        let _ = Credentials::store_at(&config_dir, &expected).unwrap();
+
+        let actual = Credentials::load_at(config_dir).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plaintext_credentials_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let config_dir = utils::config_dir();
+        let access_token = AccessToken {
+            token: "mytoken".to_string(),
+            r#type: "Bearer".to_string(),
+            expires_at: None,
+            refresh_token: None,
+        };
+        let credentials = Credentials::new(AccountInfo::default(), access_token);
+        let path =
+            Credentials::store_in(&config_dir, &credentials, CredentialsStore::File).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let mode = fs::metadata(&config_dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+
+    #[test]
+    fn encrypted_credentials_rt() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        env::remove_var("SLOT_AUTH");
+        env::set_var(PASSPHRASE_ENV_VAR, "passphrase");
+
+        let config_dir = utils::config_dir();
+
+        let access_token = AccessToken {
+            token: "mytoken".to_string(),
+            r#type: "Bearer".to_string(),
+            expires_at: None,
+            refresh_token: None,
+        };
+
+        let expected = Credentials::new(AccountInfo::default(), access_token);
+        let path =
+            Credentials::store_in(&config_dir, &expected, CredentialsStore::EncryptedFile).unwrap();
+
+        assert!(path.ends_with(ENCRYPTED_CREDENTIALS_FILE));
+        assert!(!fs::read_to_string(path).unwrap().contains("mytoken"));
+
+        let actual = Credentials::load_from(&config_dir, CredentialsStore::EncryptedFile).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn migrate_plaintext_to_encrypted_credentials() {
+        // Clear SLOT_AUTH to ensure we're testing file-based credentials
+        env::remove_var("SLOT_AUTH");
+        env::set_var(PASSPHRASE_ENV_VAR, "passphrase");
+
+        let config_dir = utils::config_dir();
+
+        let access_token = AccessToken {
+            token: "mytoken".to_string(),
+            r#type: "Bearer".to_string(),
+            expires_at: None,
+            refresh_token: None,
+        };
+
+        let expected = Credentials::new(AccountInfo::default(), access_token);
+        Credentials::store_in(&config_dir, &expected, CredentialsStore::File).unwrap();
+
+        let actual = Credentials::load_from(&config_dir, CredentialsStore::EncryptedFile).unwrap();
+        assert_eq!(expected, actual);
+
+        // the plaintext credentials must have been replaced by the encrypted ones
+        assert!(!config_dir.join(CREDENTIALS_FILE).exists());
+        assert!(config_dir.join(ENCRYPTED_CREDENTIALS_FILE).exists());
+    }
+
+    #[test]
+    fn access_token_with_expiry_format() {
+        let json = json!({
+            "token": "oauthtoken",
+            "type": "bearer",
+            "expires_at": 1700000000,
+            "refresh_token": "refreshtoken"
+        });
+
+        let token: AccessToken = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(token.expires_at, Some(1700000000));
+        assert_eq!(token.refresh_token.as_deref(), Some("refreshtoken"));
+        assert_eq!(serde_json::to_value(&token).unwrap(), json);
+    }
+
+    #[test]
+    fn access_token_expiry() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let mut token = AccessToken {
+            token: "mytoken".to_string(),
+            r#type: "Bearer".to_string(),
+            expires_at: None,
+            refresh_token: None,
+        };
+        assert!(!token.expires_within(Duration::from_secs(60)));
+
+        token.expires_at = Some(now + 3600);
+        assert!(!token.expires_within(Duration::from_secs(60)));
+        assert!(token.expires_within(Duration::from_secs(7200)));
+
+        token.expires_at = Some(now - 1);
+        assert!(token.expires_within(Duration::ZERO));
+    }
+}
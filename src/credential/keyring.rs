@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use keyring::Entry;
+
+use super::Credentials;
+use crate::error::Error;
+
+/// The service name of the keyring entries.
+const SERVICE: &str = "slot";
+/// A file, in the profile directory, referencing the keyring entry of the profile credentials.
+const KEYRING_REFERENCE_FILE: &str = "credentials.keyring";
+
+/// Stores the credentials in the OS keyring. Returns the path to the file referencing the
+/// keyring entry.
+pub(super) fn store(config_dir: &Path, credentials: &Credentials) -> Result<PathBuf, Error> {
+    let user = entry_user(config_dir);
+    let content = serde_json::to_string(credentials)?;
+    entry(&user)?
+        .set_password(&content)
+        .map_err(keyring_error)?;
+
+    let path = config_dir.join(KEYRING_REFERENCE_FILE);
+    fs::write(&path, user)?;
+    Ok(path)
+}
+
+/// Loads the credentials from the OS keyring. Returns `None` if the profile has no credentials
+/// stored in the keyring.
+pub(super) fn load(config_dir: &Path) -> Result<Option<Credentials>, Error> {
+    let path = config_dir.join(KEYRING_REFERENCE_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let user = fs::read_to_string(path)?;
+    let content = match entry(user.trim())?.get_password() {
+        Ok(content) => content,
+        Err(keyring::Error::NoEntry) => return Ok(None),
+        Err(err) => return Err(keyring_error(err)),
+    };
+
+    let credentials = serde_json::from_str(&content).map_err(|_| Error::MalformedCredentials)?;
+    Ok(Some(credentials))
+}
+
+/// Returns whether the profile has credentials stored in the keyring.
+pub(super) fn exists(config_dir: &Path) -> bool {
+    config_dir.join(KEYRING_REFERENCE_FILE).exists()
+}
+
+/// Deletes the credentials of the profile from the OS keyring, if any.
+pub(super) fn delete(config_dir: &Path) -> Result<(), Error> {
+    let path = config_dir.join(KEYRING_REFERENCE_FILE);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let user = fs::read_to_string(&path)?;
+    match entry(user.trim())?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => {}
+        Err(err) => return Err(keyring_error(err)),
+    }
+
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+/// The keyring entries are identified by the profile directory, so that each profile has its
+/// own entry.
+fn entry_user(config_dir: &Path) -> String {
+    config_dir.display().to_string()
+}
+
+fn entry(user: &str) -> Result<Entry, Error> {
+    Entry::new(SERVICE, user).map_err(keyring_error)
+}
+
+fn keyring_error(err: keyring::Error) -> Error {
+    anyhow!("keyring error: {err}").into()
+}
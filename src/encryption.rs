@@ -0,0 +1,164 @@
+use std::env;
+use std::io::IsTerminal;
+
+use anyhow::anyhow;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use dialoguer::Password;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// The environment variable holding the passphrase used to encrypt the files stored by Slot.
+pub const PASSPHRASE_ENV_VAR: &str = "SLOT_PASSPHRASE";
+
+/// The scrypt cost parameter. Kept low in tests to not slow them down.
+const SCRYPT_LOG_N: u8 = if cfg!(test) { 4 } else { 15 };
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+/// Data encrypted with a key derived from a passphrase, with scrypt and XChaCha20-Poly1305.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedData {
+    pub version: u8,
+    pub kdf: KdfParams,
+    /// The base64 encoded nonce.
+    pub nonce: String,
+    /// The base64 encoded ciphertext.
+    pub ciphertext: String,
+}
+
+/// The scrypt parameters used to derive the encryption key from the passphrase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    /// The base64 encoded salt.
+    pub salt: String,
+}
+
+impl EncryptedData {
+    const VERSION: u8 = 1;
+
+    /// Encrypts `plaintext` with a key derived from `passphrase`.
+    pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Self, Error> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let kdf = KdfParams {
+            log_n: SCRYPT_LOG_N,
+            r: SCRYPT_R,
+            p: SCRYPT_P,
+            salt: STANDARD.encode(salt),
+        };
+
+        let cipher = XChaCha20Poly1305::new(&kdf.derive_key(passphrase)?);
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), plaintext)
+            .map_err(|_| anyhow!("failed to encrypt data"))?;
+
+        Ok(Self {
+            version: Self::VERSION,
+            kdf,
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        })
+    }
+
+    /// Decrypts the data with a key derived from `passphrase`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPassphrase`] if the passphrase is not the one the data was
+    /// encrypted with.
+    pub fn decrypt(&self, passphrase: &str) -> Result<Vec<u8>, Error> {
+        if self.version != Self::VERSION {
+            return Err(anyhow!("unsupported encryption version {}", self.version).into());
+        }
+
+        let nonce = STANDARD.decode(&self.nonce).map_err(|e| anyhow!(e))?;
+        let ciphertext = STANDARD.decode(&self.ciphertext).map_err(|e| anyhow!(e))?;
+
+        if nonce.len() != NONCE_LEN {
+            return Err(anyhow!("invalid nonce length").into());
+        }
+
+        let cipher = XChaCha20Poly1305::new(&self.kdf.derive_key(passphrase)?);
+        cipher
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| Error::InvalidPassphrase)
+    }
+}
+
+impl KdfParams {
+    fn derive_key(&self, passphrase: &str) -> Result<Key, Error> {
+        let salt = STANDARD.decode(&self.salt).map_err(|e| anyhow!(e))?;
+        let params = scrypt::Params::new(self.log_n, self.r, self.p, KEY_LEN)
+            .map_err(|e| anyhow!("invalid scrypt parameters: {e}"))?;
+
+        let mut key = Key::default();
+        scrypt::scrypt(passphrase.as_bytes(), &salt, &params, &mut key)
+            .map_err(|e| anyhow!("failed to derive key: {e}"))?;
+
+        Ok(key)
+    }
+}
+
+/// Returns the passphrase used to encrypt the files stored by Slot.
+///
+/// The passphrase is read from the `SLOT_PASSPHRASE` environment variable, or prompted if the
+/// standard input is a terminal. Set `confirm` to ask the user to type a new passphrase twice.
+pub fn passphrase(prompt: &str, confirm: bool) -> Result<String, Error> {
+    if let Ok(passphrase) = env::var(PASSPHRASE_ENV_VAR) {
+        return Ok(passphrase);
+    }
+
+    if !std::io::stdin().is_terminal() {
+        return Err(anyhow!(
+            "a passphrase is required, set it with the `{PASSPHRASE_ENV_VAR}` environment variable"
+        )
+        .into());
+    }
+
+    let mut input = Password::new().with_prompt(prompt);
+    if confirm {
+        input = input.with_confirmation("Confirm passphrase", "Passphrases don't match");
+    }
+
+    Ok(input.interact().map_err(|e| anyhow!(e))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::EncryptedData;
+    use crate::Error;
+
+    #[test]
+    fn encryption_rt() {
+        let plaintext = b"my secret";
+
+        let encrypted = EncryptedData::encrypt(plaintext, "passphrase").unwrap();
+        assert!(!encrypted.ciphertext.contains("my secret"));
+
+        let decrypted = encrypted.decrypt("passphrase").unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn decrypt_with_wrong_passphrase() {
+        let encrypted = EncryptedData::encrypt(b"my secret", "passphrase").unwrap();
+        let result = encrypted.decrypt("wrong passphrase");
+        assert_matches!(result, Err(Error::InvalidPassphrase));
+    }
+}
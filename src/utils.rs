@@ -0,0 +1,150 @@
+use regex::Regex;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// The default directory name where the Slot-generated files (e.g credentials/session keys) are stored.
+const SLOT_DIR: &str = "slot";
+
+/// Static instance of the email validation regex, compiled once on first use.
+static EMAIL_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Get the path to the config directory where the Slot-generated files (e.g credentials/session keys) are stored.
+///  This function guarantees that the config directory exists.
+///
+/// If this function is called in a test environment, path to a temporary directory is returned instead.
+pub fn config_dir() -> PathBuf {
+    let path = if cfg!(test) {
+        tempfile::tempdir().unwrap().keep()
+    } else {
+        dirs::config_local_dir().expect("unsupported OS")
+    }
+    .join(SLOT_DIR);
+
+    if path.exists() {
+        path
+    } else {
+        fs::create_dir_all(&path).expect("failed to create config directory");
+        path
+    }
+}
+
+/// Writes `contents` to `path`, only readable and writable by the owner of the file. On Unix, the
+/// parent directory is also restricted to its owner.
+#[cfg(unix)]
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    if let Some(parent) = path.parent() {
+        fs::set_permissions(parent, fs::Permissions::from_mode(0o700))?;
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+
+    // The mode is only applied to new files.
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    fs::write(path, contents)
+}
+
+/// Validates if the provided string is a valid email address format.
+///
+/// Uses a regex pattern to check for basic email format:
+/// - Local part: alphanumeric characters, dots, hyphens, underscores
+/// - @ symbol
+/// - Domain part: alphanumeric characters, dots, hyphens
+/// - At least one dot in domain part
+///
+/// # Arguments
+/// * `email` - The email string to validate
+///
+/// # Returns
+/// * `true` if the email format is valid, `false` otherwise
+pub fn is_valid_email(email: &str) -> bool {
+    let regex = EMAIL_REGEX.get_or_init(|| {
+        Regex::new(r"^[a-zA-Z0-9]([a-zA-Z0-9._%+-]*[a-zA-Z0-9])?@[a-zA-Z0-9]([a-zA-Z0-9.-]*[a-zA-Z0-9])?\.[a-zA-Z]{2,}$").unwrap()
+    });
+    regex.is_match(email)
+        && !email.contains("..")
+        && !email.starts_with('.')
+        && !email.ends_with('.')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::SLOT_DIR;
+
+    #[test]
+    fn config_dir_must_exist() {
+        let path = super::config_dir();
+        assert!(path.exists());
+        assert!(path.ends_with(SLOT_DIR));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_private_restricts_permissions() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = super::config_dir().join("account");
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("secret.json");
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        super::write_private(&path, b"secret").unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let mode = fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        assert_eq!(fs::read(&path).unwrap(), b"secret");
+    }
+
+    #[test]
+    fn test_valid_emails() {
+        assert!(super::is_valid_email("test@example.com"));
+        assert!(super::is_valid_email("user.name@domain.co.uk"));
+        assert!(super::is_valid_email("firstname+lastname@example.org"));
+        assert!(super::is_valid_email("test_email@sub.domain.com"));
+    }
+
+    #[test]
+    fn test_invalid_emails() {
+        assert!(!super::is_valid_email("invalid-email"));
+        assert!(!super::is_valid_email("@example.com"));
+        assert!(!super::is_valid_email("test@"));
+        assert!(!super::is_valid_email("test@.com"));
+        assert!(!super::is_valid_email("test@domain"));
+        assert!(!super::is_valid_email(""));
+        assert!(!super::is_valid_email("test..email@example.com"));
+    }
+
+    #[test]
+    fn test_edge_case_emails() {
+        // Single character local/domain parts
+        assert!(super::is_valid_email("a@b.com"));
+        assert!(super::is_valid_email("x@example.co"));
+
+        // Valid special characters
+        assert!(super::is_valid_email("test-email@example.com"));
+        assert!(super::is_valid_email("user_name@sub-domain.com"));
+
+        // Domain with numbers
+        assert!(super::is_valid_email("test@123domain.com"));
+    }
+}
@@ -0,0 +1,474 @@
+use colored::*;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::Confirm;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::{exit, Command};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use update_informer::{registry, Check};
+
+use crate::utils;
+
+pub use self::updater::{Updater, RELEASES_URL};
+
+mod updater;
+
+/// Repository name for version checking
+pub const REPO_NAME: &str = "cartridge-gg/slot";
+
+/// Name of the file pinning the version of slot used in a project
+pub const PIN_FILE_NAME: &str = ".slot-version";
+
+/// How long the result of the latest version check is cached
+pub const VERSION_CHECK_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Name of the file caching the latest version check, in the config directory
+const VERSION_CHECK_FILE_NAME: &str = "version-check.json";
+
+/// The cached result of the latest version check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct VersionCheck {
+    /// UNIX timestamp of the check, in seconds
+    checked_at: u64,
+    /// The newest version available at the time of the check, if newer than the version that
+    /// was running
+    latest: Option<String>,
+}
+
+impl VersionCheck {
+    fn is_fresh(&self, now: u64, ttl: Duration) -> bool {
+        now.saturating_sub(self.checked_at) < ttl.as_secs()
+    }
+}
+
+/// Checks if a new version is available and returns it if so
+///
+/// The result of the check is cached on disk for [`VERSION_CHECK_TTL`].
+pub fn get_latest_version() -> Option<String> {
+    let current = env!("CARGO_PKG_VERSION");
+    let cache_path = utils::config_dir().join(VERSION_CHECK_FILE_NAME);
+    let now = now();
+
+    let cached =
+        read_version_check(&cache_path).filter(|check| check.is_fresh(now, VERSION_CHECK_TTL));
+    let latest = match cached {
+        Some(check) => check.latest,
+        None => match check_latest_version(&cache_path, current, now) {
+            Ok(latest) => latest,
+            Err(_) => {
+                eprintln!("error checking for new version");
+                return None;
+            }
+        },
+    };
+
+    // The cached version may have been installed since the check.
+    latest.filter(|latest| is_newer(latest, current))
+}
+
+/// Checks if a new version is available and returns it if so, without using the cached result
+///
+/// The result of the check replaces the cached one.
+pub fn fetch_latest_version() -> anyhow::Result<Option<String>> {
+    let cache_path = utils::config_dir().join(VERSION_CHECK_FILE_NAME);
+    check_latest_version(&cache_path, env!("CARGO_PKG_VERSION"), now())
+}
+
+/// Queries GitHub for a version newer than `current`, and caches the result at `cache_path`
+fn check_latest_version(
+    cache_path: &Path,
+    current: &str,
+    now: u64,
+) -> anyhow::Result<Option<String>> {
+    // The check is cached by us, so the informer must always query the registry.
+    let informer =
+        update_informer::new(registry::GitHub, REPO_NAME, current).interval(Duration::ZERO);
+    let latest = informer
+        .check_version()
+        .map_err(|e| anyhow::anyhow!("failed to check for a new version: {e}"))?;
+
+    let check = VersionCheck {
+        checked_at: now,
+        latest: latest.map(|v| v.to_string()),
+    };
+    // Failing to cache the check only means it will be done again next time.
+    let _ = write_version_check(cache_path, &check);
+    Ok(check.latest)
+}
+
+fn read_version_check(path: &Path) -> Option<VersionCheck> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn write_version_check(path: &Path, check: &VersionCheck) -> anyhow::Result<()> {
+    fs::write(path, serde_json::to_string(check)?)?;
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Parses a version like `v1.2.3` or `1.2.3` into its numeric components
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    // Ignore pre-release and build metadata.
+    let version = version.split(['-', '+']).next()?;
+
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Returns whether `version` is newer than `current`
+fn is_newer(version: &str, current: &str) -> bool {
+    match (parse_version(version), parse_version(current)) {
+        (Some(version), Some(current)) => version > current,
+        _ => false,
+    }
+}
+
+/// Returns whether both versions are the same, ignoring the `v` prefix
+pub fn is_same_version(a: &str, b: &str) -> bool {
+    match (parse_version(a), parse_version(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.trim() == b.trim(),
+    }
+}
+
+/// Finds the version pinned by a [`PIN_FILE_NAME`] file in `dir` or its ancestors
+pub fn find_pinned_version(dir: &Path) -> Option<(PathBuf, String)> {
+    dir.ancestors().find_map(|dir| {
+        let path = dir.join(PIN_FILE_NAME);
+        let version = fs::read_to_string(&path).ok()?;
+        let version = version.trim();
+        (!version.is_empty()).then(|| (path, version.to_string()))
+    })
+}
+
+/// Returns the version pinned for the current directory, if any
+pub fn pinned_version() -> Option<String> {
+    let dir = env::current_dir().ok()?;
+    find_pinned_version(&dir).map(|(_, version)| version)
+}
+
+/// Pins the version of slot used in `dir`, by writing a [`PIN_FILE_NAME`] file
+pub fn pin_version(dir: &Path, version: &str) -> anyhow::Result<PathBuf> {
+    anyhow::ensure!(
+        parse_version(version).is_some(),
+        "invalid version `{version}`, expected e.g. 0.58.0"
+    );
+
+    let path = dir.join(PIN_FILE_NAME);
+    fs::write(
+        &path,
+        format!("{}\n", version.trim().trim_start_matches('v')),
+    )?;
+    Ok(path)
+}
+
+/// Prints a notification about a new version being available, to stderr
+pub fn notify_new_version(current_version: &str, latest_version: &str) {
+    let message = format!(
+        "\n{} {}{} → {}",
+        "Slot CLI update available:".bold(),
+        "v".red().bold(),
+        current_version.red().bold(),
+        latest_version.green().bold()
+    );
+
+    let upgrade_message = format!("To upgrade, run: {}", "`slot self update`".cyan().bold());
+
+    eprintln!("{}", message);
+    eprintln!("{}", upgrade_message);
+    eprintln!("\n");
+}
+
+/// Prints a notification about the project pinning an older version than the running one, to
+/// stderr
+pub fn notify_pinned_downgrade(current_version: &str, pinned_version: &str) {
+    eprintln!(
+        "{}",
+        pinned_downgrade_message(current_version, pinned_version)
+    );
+    eprintln!(
+        "To install it, run: {}\n",
+        "`slot self update`".cyan().bold()
+    );
+}
+
+fn pinned_downgrade_message(current_version: &str, pinned_version: &str) -> String {
+    format!(
+        "\n{} {}{} → {} ({PIN_FILE_NAME})",
+        "This project pins an older Slot CLI:".bold(),
+        "v".yellow().bold(),
+        current_version.yellow().bold(),
+        pinned_version.yellow().bold()
+    )
+}
+
+/// Checks if auto-update is disabled via environment variable (SLOT_DISABLE_AUTO_UPDATE)
+pub fn is_auto_update_disabled() -> bool {
+    env::var("SLOT_DISABLE_AUTO_UPDATE").is_ok()
+}
+
+/// Checks if auto-update should be forced without confirmation (SLOT_FORCE_AUTO_UPDATE)
+pub fn is_auto_update_forced() -> bool {
+    env::var("SLOT_FORCE_AUTO_UPDATE").is_ok()
+}
+
+/// Checks if the user can be prompted, i.e. stdin and stdout are terminals
+pub fn is_interactive() -> bool {
+    std::io::stdin().is_terminal() && std::io::stdout().is_terminal()
+}
+
+/// Detects if the current process is being run via `cargo run`
+pub fn is_running_via_cargo_run() -> bool {
+    // Get the current executable path
+    if let Ok(current_exe) = env::current_exe() {
+        // Check if the path contains "target/debug" or "target/release"
+        // which would indicate it's being run via cargo
+        let path_str = current_exe.to_string_lossy();
+        if path_str.contains("/target/debug/") || path_str.contains("/target/release/") {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Installs the given version of the Slot CLI in place of the running executable
+///
+/// The replaced binary is kept for [`rollback`].
+pub async fn update(version: &str) -> anyhow::Result<()> {
+    Updater::new()?.install(version).await
+}
+
+/// Rolls the running executable back to the version it was updated from
+pub fn rollback() -> anyhow::Result<()> {
+    Updater::new()?.rollback()
+}
+
+/// Updates the CLI to `version`
+/// If update is successful, re-executes the current command with the updated version
+pub async fn run_auto_update(version: &str) -> bool {
+    if let Err(e) = update(version).await {
+        eprintln!("Update failed: {e}");
+        return false;
+    }
+
+    eprintln!("Update successful! Re-executing command with new version...");
+
+    // Re-execute the current command with the updated version
+    re_execute_current_command();
+
+    // This line should never be reached as re_execute_current_command will exit
+    // But we return true just in case
+    true
+}
+
+/// Re-executes the current command with all its arguments
+/// This function will exit the current process
+pub fn re_execute_current_command() {
+    // The updated binary replaced the running executable
+    let slot_bin_path = match env::current_exe() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Failed to locate the updated binary: {}", e);
+            exit(1);
+        }
+    };
+
+    // Get all command line arguments
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    eprintln!("Re-executing with binary: {:?}", slot_bin_path);
+
+    // Execute the command with the installed binary path
+    let result = Command::new(slot_bin_path).args(args).status();
+
+    // Exit with the same status code as the re-executed command
+    match result {
+        Ok(status) => exit(status.code().unwrap_or(0)),
+        Err(e) => {
+            eprintln!("Failed to re-execute command: {}", e);
+            exit(1);
+        }
+    };
+}
+
+/// Checks for a new version and runs auto-update if needed
+///
+/// If the project pins a version of slot with a `.slot-version` file, that version is used
+/// instead of the latest one. A pinned version older than the running one is never installed
+/// without confirmation, which defaults to no. The user is never prompted when stdin or stdout
+/// isn't a terminal.
+/// Everything is printed to stderr, so that the output of the command can still be piped.
+///
+/// Behavior is controlled by environment variables:
+/// - SLOT_DISABLE_AUTO_UPDATE: Disables auto-update completely
+/// - SLOT_FORCE_AUTO_UPDATE: Forces auto-update without confirmation
+///
+/// Returns true if an update was performed
+pub async fn check_and_auto_update() -> bool {
+    // Skip auto-update if disabled or running via cargo
+    if is_running_via_cargo_run() {
+        return false;
+    }
+
+    let current = env!("CARGO_PKG_VERSION");
+    let pinned = pinned_version();
+
+    let version = match &pinned {
+        Some(pinned) if is_same_version(pinned, current) => return false,
+        Some(pinned) => pinned.clone(),
+        // The check queries GitHub synchronously, so it must not block the runtime.
+        None => match tokio::task::spawn_blocking(get_latest_version).await {
+            Ok(Some(version)) => version,
+            _ => return false,
+        },
+    };
+
+    // A project may pin a version older than the running one.
+    let downgrade = is_newer(current, &version);
+    let notify = || {
+        if downgrade {
+            notify_pinned_downgrade(current, &version);
+        } else {
+            notify_new_version(current, &version);
+        }
+    };
+
+    if is_auto_update_disabled() {
+        // Still notify the user, but don't auto-update
+        notify();
+        return false;
+    }
+
+    if pinned.is_some() && !downgrade {
+        eprintln!(
+            "This project pins Slot CLI to {} ({PIN_FILE_NAME}).",
+            version.green().bold()
+        );
+    }
+
+    // If auto-update is forced, run it without confirmation. Downgrades are never forced.
+    if is_auto_update_forced() && !downgrade {
+        eprintln!("New version available: {} → {}", current, version);
+        eprintln!("Auto-updating (SLOT_FORCE_AUTO_UPDATE is set)...");
+        return run_auto_update(&version).await;
+    }
+
+    // Never prompt when running non-interactively, e.g. in CI
+    if !is_interactive() {
+        notify();
+        return false;
+    }
+
+    // Otherwise, prompt for confirmation using dialoguer
+    let prompt = if downgrade {
+        eprintln!("{}", pinned_downgrade_message(current, &version));
+        "Do you want to install the pinned version?"
+    } else {
+        eprintln!(
+            "\n{} {}{} → {}",
+            "Slot CLI update available:".bold(),
+            "v".red().bold(),
+            current.red().bold(),
+            version.green().bold()
+        );
+        "Do you want to update now (recommended)?"
+    };
+
+    let confirmation = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(!downgrade)
+        .show_default(true)
+        .wait_for_newline(true)
+        .interact()
+        .unwrap_or(false);
+
+    if confirmation {
+        eprintln!("Installing Slot CLI {version} first...");
+        return run_auto_update(&version).await;
+    }
+
+    // User declined the update, just show the notification
+    eprintln!(
+        "Update skipped. You can update manually by running: {}",
+        "`slot self update`".cyan().bold()
+    );
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_check_ttl() {
+        let check = VersionCheck {
+            checked_at: 1_000,
+            latest: Some("0.59.0".to_string()),
+        };
+
+        let ttl = Duration::from_secs(100);
+        assert!(check.is_fresh(1_050, ttl));
+        assert!(!check.is_fresh(1_100, ttl));
+    }
+
+    #[test]
+    fn version_check_cache_roundtrip() {
+        let path = utils::config_dir().join(VERSION_CHECK_FILE_NAME);
+        assert_eq!(read_version_check(&path), None);
+
+        let check = VersionCheck {
+            checked_at: 42,
+            latest: None,
+        };
+        write_version_check(&path, &check).unwrap();
+        assert_eq!(read_version_check(&path), Some(check));
+    }
+
+    #[test]
+    fn compare_versions() {
+        assert_eq!(parse_version("v1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2.3-alpha.1"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2"), None);
+        assert_eq!(parse_version("nightly"), None);
+
+        assert!(is_newer("0.59.0", "0.58.0"));
+        assert!(is_newer("v1.0.0", "0.58.10"));
+        assert!(!is_newer("0.58.0", "0.58.0"));
+        assert!(!is_newer("nightly", "0.58.0"));
+
+        assert!(is_same_version("v0.58.0", "0.58.0"));
+        assert!(!is_same_version("0.58.1", "0.58.0"));
+    }
+
+    #[test]
+    fn pinned_version_in_ancestors() {
+        let root = tempfile::tempdir().unwrap();
+        let nested = root.path().join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(find_pinned_version(&nested), None);
+
+        let path = pin_version(root.path(), "v0.57.0").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "0.57.0\n");
+
+        let (found, version) = find_pinned_version(&nested).unwrap();
+        assert_eq!(found, path);
+        assert_eq!(version, "0.57.0");
+
+        assert!(pin_version(root.path(), "latest").is_err());
+    }
+}